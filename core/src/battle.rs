@@ -1,42 +1,45 @@
+use crate::enemy_spec::EnemySpec;
 use crate::event::Event;
 use crate::log::push_event;
-use crate::model::{BattleOutcome, BattleState, Team, Unit};
+use crate::model::{BattleOutcome, BattleState, HeroState, RunState, Team, Unit};
 use crate::rng::SimpleRng;
 
 fn hp2(v: f32) -> f32 {
     (v * 100.0).round() / 100.0
 }
 
-/// Creates a normal battle with one player unit and a small enemy pack.
-pub fn create_battle(
-    player_hp: f32,
-    player_max_hp: f32,
-    player_atk: i32,
-    player_speed: f32,
-    enemy_count: u32,
-    enemy_hp: f32,
-    enemy_atk: i32,
-    enemy_speed: f32,
-) -> BattleState {
+/// Creates a battle with one unit per hero followed by one enemy unit per bestiary spec.
+/// Unit ids follow that order, so heroes are `0..party.len()`.
+/// Fallen heroes are spawned at 0 HP and never act.
+pub fn create_battle(party: &[HeroState], enemies: &[&'static EnemySpec]) -> BattleState {
     let mut units = Vec::new();
-    units.push(Unit {
-        id: 0,
-        team: Team::Player,
-        hp: hp2(player_hp),
-        max_hp: hp2(player_max_hp),
-        atk: player_atk,
-        speed: player_speed,
-        action_gauge: 0.0,
-    });
-
-    for idx in 0..enemy_count {
+    for (slot, hero) in party.iter().enumerate() {
         units.push(Unit {
-            id: idx + 1,
+            id: slot as u32,
+            team: Team::Player,
+            name: hero.name,
+            enemy_id: None,
+            party_slot: Some(slot),
+            hp: hp2(hero.hp),
+            max_hp: hp2(hero.max_hp),
+            atk: hero.atk,
+            speed: hero.speed,
+            action_gauge: 0.0,
+        });
+    }
+
+    let first_enemy_id = units.len() as u32;
+    for (idx, spec) in enemies.iter().enumerate() {
+        units.push(Unit {
+            id: first_enemy_id + idx as u32,
             team: Team::Enemy,
-            hp: hp2(enemy_hp),
-            max_hp: hp2(enemy_hp),
-            atk: enemy_atk,
-            speed: enemy_speed,
+            name: spec.name,
+            enemy_id: Some(spec.id),
+            party_slot: None,
+            hp: hp2(spec.max_hp),
+            max_hp: hp2(spec.max_hp),
+            atk: spec.atk,
+            speed: spec.speed,
             action_gauge: 0.0,
         });
     }
@@ -103,6 +106,7 @@ pub fn run_battle(
 
             let actor_team = state.units[actor_idx].team;
             let actor = team_to_actor(actor_team);
+            let actor_id = state.units[actor_idx].id;
             let target_team = if actor_team == Team::Player {
                 Team::Enemy
             } else {
@@ -129,12 +133,14 @@ pub fn run_battle(
             let target_idx = target_indices[rng.range_usize(target_indices.len())];
             let damage = (state.units[actor_idx].atk as f32).max(0.01);
             let target = team_to_actor(target_team);
+            let target_id = state.units[target_idx].id;
 
-            push_event(logs, Event::TurnReady { actor });
+            push_event(logs, Event::TurnReady { actor, actor_id });
             push_event(
                 logs,
                 Event::ActionUsed {
                     actor,
+                    actor_id,
                     action_name: "basic_attack",
                 },
             );
@@ -145,9 +151,17 @@ pub fn run_battle(
                 logs,
                 Event::DamageDealt {
                     src: actor,
+                    src_id: actor_id,
                     dst: target,
+                    dst_id: target_id,
+                    damage_type: "physical",
                     amount: damage,
+                    absorbed: 0.0,
+                    hp_damage: damage,
                     dst_hp_after: state.units[target_idx].hp,
+                    crit: false,
+                    miss: false,
+                    breakdown: Vec::new(),
                 },
             );
 
@@ -155,18 +169,27 @@ pub fn run_battle(
                 logs,
                 Event::StatusApplied {
                     src: actor,
+                    src_id: actor_id,
                     dst: target,
+                    dst_id: target_id,
                     status: "burn",
                     stacks: 1,
+                    added: 1,
                     duration: 1,
                 },
             );
             push_event(
                 logs,
                 Event::StatusTick {
+                    src: actor,
+                    src_id: actor_id,
                     dst: target,
+                    dst_id: target_id,
                     status: "burn",
+                    damage_type: "fire",
                     amount: 0.0,
+                    absorbed: 0.0,
+                    hp_damage: 0.0,
                     dst_hp_after: state.units[target_idx].hp,
                 },
             );
@@ -174,6 +197,7 @@ pub fn run_battle(
                 logs,
                 Event::StatusExpired {
                     dst: target,
+                    dst_id: target_id,
                     status: "burn",
                 },
             );
@@ -212,13 +236,23 @@ pub fn run_battle(
     BattleOutcome::Defeat
 }
 
+/// Combined HP of every hero unit.
 pub fn player_hp_after_battle(state: &BattleState) -> f32 {
-    state
+    hp2(state
         .units
         .iter()
-        .find(|u| u.team == Team::Player)
+        .filter(|u| u.team == Team::Player)
         .map(|u| u.hp)
-        .unwrap_or(0.0)
+        .sum())
+}
+
+/// Copies hero HP from the battle back into the run party.
+pub fn store_party_hp(run: &mut RunState, state: &BattleState) {
+    for unit in &state.units {
+        if let Some(hero) = unit.party_slot.and_then(|slot| run.party.get_mut(slot)) {
+            hero.hp = hp2(unit.hp);
+        }
+    }
 }
 
 fn has_alive(units: &[Unit], team: Team) -> bool {
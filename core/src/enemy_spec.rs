@@ -0,0 +1,412 @@
+use crate::model::NodeType;
+use crate::rng::SimpleRng;
+use crate::skill::{
+    Condition, DamageType, SkillId, StatusType, BASIC_ATTACK, CORE_OVERLOAD, CRUSHING_SLAM,
+    FROST_SPIT, ICE_CARAPACE, REND, SCORCH, STATIC_BITE,
+};
+use crate::trait_spec::{TraitId, FROSTBOUND, PACK_HUNTER, SEARING_CORE, VAMPIRIC};
+
+pub type EnemyId = &'static str;
+
+/// One row of a priority list: the skill is used when the condition holds.
+/// Conditions are evaluated with the enemy as source/owner and its intended target as dst.
+#[derive(Clone, Copy, Debug)]
+pub struct PolicyRule {
+    pub condition: Condition,
+    pub skill: SkillId,
+}
+
+/// How an enemy picks the next skill from its loadout.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum EnemyPolicy {
+    /// Weights line up with `EnemySpec::skills`; missing weights count as 0.
+    WeightedRandom(&'static [u32]),
+    /// Cycles through `EnemySpec::skills` in order.
+    Rotation,
+    /// First matching rule wins; falls back to the first loadout skill.
+    Priority(&'static [PolicyRule]),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct EnemySpec {
+    pub id: EnemyId,
+    pub name: &'static str,
+    pub max_hp: f32,
+    pub atk: i32,
+    pub speed: f32,
+    pub skills: &'static [SkillId],
+    pub policy: EnemyPolicy,
+    pub traits: &'static [TraitId],
+    /// Cuts physical hits to `100 / (100 + armor)`.
+    pub armor: f32,
+    /// Missing damage types take no bonus or reduction.
+    pub resistances: &'static [Resistance],
+    /// Subtracted from the attacker's accuracy.
+    pub evasion: f32,
+}
+
+/// Fraction of `damage_type` damage ignored; negative values are weaknesses.
+#[derive(Clone, Copy, Debug)]
+pub struct Resistance {
+    pub damage_type: DamageType,
+    pub value: f32,
+}
+
+/// One weighted row of an encounter table, valid on floors `min_floor..=max_floor`.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct EncounterSpec {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub enemies: &'static [EnemyId],
+    pub min_floor: u32,
+    pub max_floor: u32,
+    pub weight: u32,
+}
+
+pub const ROGUE_DRONE: EnemySpec = EnemySpec {
+    id: "rogue_drone",
+    name: "Rogue Drone",
+    max_hp: 84.0,
+    atk: 11,
+    speed: 28.0,
+    skills: &[BASIC_ATTACK.id, STATIC_BITE.id],
+    policy: EnemyPolicy::WeightedRandom(&[3, 1]),
+    traits: &[],
+    armor: 15.0,
+    resistances: &[Resistance {
+        damage_type: DamageType::Lightning,
+        value: -0.25,
+    }],
+    evasion: 0.05,
+};
+
+pub const SCRAP_HOUND: EnemySpec = EnemySpec {
+    id: "scrap_hound",
+    name: "Scrap Hound",
+    max_hp: 70.0,
+    atk: 9,
+    speed: 36.0,
+    skills: &[BASIC_ATTACK.id, REND.id],
+    policy: EnemyPolicy::WeightedRandom(&[2, 1]),
+    traits: &[PACK_HUNTER.id, VAMPIRIC.id],
+    armor: 5.0,
+    resistances: &[Resistance {
+        damage_type: DamageType::Ice,
+        value: -0.2,
+    }],
+    evasion: 0.1,
+};
+
+pub const EMBER_WISP: EnemySpec = EnemySpec {
+    id: "ember_wisp",
+    name: "Ember Wisp",
+    max_hp: 66.0,
+    atk: 12,
+    speed: 33.0,
+    skills: &[SCORCH.id, BASIC_ATTACK.id],
+    policy: EnemyPolicy::Rotation,
+    traits: &[],
+    armor: 0.0,
+    resistances: &[
+        Resistance {
+            damage_type: DamageType::Fire,
+            value: 0.6,
+        },
+        Resistance {
+            damage_type: DamageType::Ice,
+            value: -0.5,
+        },
+    ],
+    evasion: 0.15,
+};
+
+pub const RUST_SENTINEL: EnemySpec = EnemySpec {
+    id: "rust_sentinel",
+    name: "Rust Sentinel",
+    max_hp: 118.0,
+    atk: 12,
+    speed: 22.0,
+    skills: &[BASIC_ATTACK.id, BASIC_ATTACK.id, CRUSHING_SLAM.id],
+    policy: EnemyPolicy::Rotation,
+    traits: &[],
+    armor: 40.0,
+    resistances: &[Resistance {
+        damage_type: DamageType::Lightning,
+        value: -0.3,
+    }],
+    evasion: 0.0,
+};
+
+pub const VOLT_STALKER: EnemySpec = EnemySpec {
+    id: "volt_stalker",
+    name: "Volt Stalker",
+    max_hp: 96.0,
+    atk: 13,
+    speed: 34.0,
+    skills: &[STATIC_BITE.id, BASIC_ATTACK.id],
+    policy: EnemyPolicy::WeightedRandom(&[1, 1]),
+    traits: &[PACK_HUNTER.id],
+    armor: 10.0,
+    resistances: &[
+        Resistance {
+            damage_type: DamageType::Lightning,
+            value: 0.5,
+        },
+        Resistance {
+            damage_type: DamageType::Fire,
+            value: -0.25,
+        },
+    ],
+    evasion: 0.2,
+};
+
+/// Shatters frozen targets, sometimes hardens its shell, otherwise keeps targets frozen.
+const FROST_WARDEN_RULES: [PolicyRule; 3] = [
+    PolicyRule {
+        condition: Condition::TargetHasStatus(StatusType::Freeze),
+        skill: CRUSHING_SLAM.id,
+    },
+    PolicyRule {
+        condition: Condition::RandomRollBelow(0.25),
+        skill: ICE_CARAPACE.id,
+    },
+    PolicyRule {
+        condition: Condition::RandomRollBelow(0.6),
+        skill: FROST_SPIT.id,
+    },
+];
+
+pub const FROST_WARDEN: EnemySpec = EnemySpec {
+    id: "frost_warden",
+    name: "Frost Warden",
+    max_hp: 200.0,
+    atk: 13,
+    speed: 30.0,
+    skills: &[
+        BASIC_ATTACK.id,
+        FROST_SPIT.id,
+        CRUSHING_SLAM.id,
+        ICE_CARAPACE.id,
+    ],
+    policy: EnemyPolicy::Priority(&FROST_WARDEN_RULES),
+    traits: &[FROSTBOUND.id],
+    armor: 25.0,
+    resistances: &[
+        Resistance {
+            damage_type: DamageType::Ice,
+            value: 0.5,
+        },
+        Resistance {
+            damage_type: DamageType::Fire,
+            value: -0.3,
+        },
+    ],
+    evasion: 0.0,
+};
+
+/// Overloads once the target is carrying a pile of statuses, otherwise feeds Burn.
+const OVERSTACK_CORE_RULES: [PolicyRule; 2] = [
+    PolicyRule {
+        condition: Condition::TargetStatusCountAtLeast(2),
+        skill: CORE_OVERLOAD.id,
+    },
+    PolicyRule {
+        condition: Condition::RandomRollBelow(0.5),
+        skill: SCORCH.id,
+    },
+];
+
+pub const OVERSTACK_CORE: EnemySpec = EnemySpec {
+    id: "overstack_core",
+    name: "Overstack Core",
+    max_hp: 220.0,
+    atk: 14,
+    speed: 32.0,
+    skills: &[BASIC_ATTACK.id, SCORCH.id, CORE_OVERLOAD.id],
+    policy: EnemyPolicy::Priority(&OVERSTACK_CORE_RULES),
+    traits: &[SEARING_CORE.id],
+    armor: 20.0,
+    resistances: &[
+        Resistance {
+            damage_type: DamageType::Fire,
+            value: 0.4,
+        },
+        Resistance {
+            damage_type: DamageType::Ice,
+            value: -0.25,
+        },
+    ],
+    evasion: 0.05,
+};
+
+pub const NORMAL_ENCOUNTERS: [EncounterSpec; 8] = [
+    EncounterSpec {
+        id: "drone_patrol",
+        name: "Rogue Drone",
+        enemies: &[ROGUE_DRONE.id],
+        min_floor: 1,
+        max_floor: 6,
+        weight: 40,
+    },
+    EncounterSpec {
+        id: "hound_ambush",
+        name: "Scrap Hound",
+        enemies: &[SCRAP_HOUND.id],
+        min_floor: 1,
+        max_floor: 8,
+        weight: 30,
+    },
+    EncounterSpec {
+        id: "wisp_flare",
+        name: "Ember Wisp",
+        enemies: &[EMBER_WISP.id],
+        min_floor: 2,
+        max_floor: 12,
+        weight: 25,
+    },
+    EncounterSpec {
+        id: "sentinel_post",
+        name: "Rust Sentinel",
+        enemies: &[RUST_SENTINEL.id],
+        min_floor: 4,
+        max_floor: 12,
+        weight: 25,
+    },
+    EncounterSpec {
+        id: "stalker_hunt",
+        name: "Volt Stalker",
+        enemies: &[VOLT_STALKER.id],
+        min_floor: 7,
+        max_floor: 12,
+        weight: 35,
+    },
+    EncounterSpec {
+        id: "hound_pack",
+        name: "Hound Pack",
+        enemies: &[SCRAP_HOUND.id, SCRAP_HOUND.id],
+        min_floor: 3,
+        max_floor: 10,
+        weight: 20,
+    },
+    EncounterSpec {
+        id: "drone_escort",
+        name: "Drone Escort",
+        enemies: &[ROGUE_DRONE.id, EMBER_WISP.id],
+        min_floor: 5,
+        max_floor: 12,
+        weight: 20,
+    },
+    EncounterSpec {
+        id: "storm_pack",
+        name: "Storm Pack",
+        enemies: &[VOLT_STALKER.id, SCRAP_HOUND.id, SCRAP_HOUND.id],
+        min_floor: 9,
+        max_floor: 12,
+        weight: 15,
+    },
+];
+
+pub const BOSS_ENCOUNTERS: [EncounterSpec; 2] = [
+    EncounterSpec {
+        id: "frost_warden",
+        name: "Frost Warden",
+        enemies: &[FROST_WARDEN.id],
+        min_floor: 1,
+        max_floor: 6,
+        weight: 1,
+    },
+    EncounterSpec {
+        id: "overstack_core",
+        name: "Overstack Core",
+        enemies: &[OVERSTACK_CORE.id],
+        min_floor: 7,
+        max_floor: u32::MAX,
+        weight: 1,
+    },
+];
+
+pub fn enemy_by_id(id: &str) -> Option<&'static EnemySpec> {
+    match id {
+        "rogue_drone" => Some(&ROGUE_DRONE),
+        "scrap_hound" => Some(&SCRAP_HOUND),
+        "ember_wisp" => Some(&EMBER_WISP),
+        "rust_sentinel" => Some(&RUST_SENTINEL),
+        "volt_stalker" => Some(&VOLT_STALKER),
+        "frost_warden" => Some(&FROST_WARDEN),
+        "overstack_core" => Some(&OVERSTACK_CORE),
+        _ => None,
+    }
+}
+
+pub fn encounter_table(node_type: NodeType) -> &'static [EncounterSpec] {
+    match node_type {
+        NodeType::Boss => &BOSS_ENCOUNTERS,
+        _ => &NORMAL_ENCOUNTERS,
+    }
+}
+
+/// Weighted pick among the table rows that allow `floor`.
+/// Falls back to the first row when no row covers the floor.
+pub fn pick_encounter(
+    node_type: NodeType,
+    floor: u32,
+    rng: &mut SimpleRng,
+) -> &'static EncounterSpec {
+    let table = encounter_table(node_type);
+    let eligible: Vec<&'static EncounterSpec> = table
+        .iter()
+        .filter(|e| floor >= e.min_floor && floor <= e.max_floor && e.weight > 0)
+        .collect();
+    if eligible.is_empty() {
+        return &table[0];
+    }
+
+    let total: u32 = eligible.iter().map(|e| e.weight).sum();
+    let mut roll = rng.range_usize(total as usize) as u32;
+    for encounter in &eligible {
+        if roll < encounter.weight {
+            return encounter;
+        }
+        roll -= encounter.weight;
+    }
+    eligible[0]
+}
+
+pub fn encounter_enemies(encounter: &EncounterSpec) -> Vec<&'static EnemySpec> {
+    encounter
+        .enemies
+        .iter()
+        .filter_map(|id| enemy_by_id(id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encounter_enemies, pick_encounter, OVERSTACK_CORE};
+    use crate::model::NodeType;
+    use crate::rng::SimpleRng;
+
+    #[test]
+    fn encounters_respect_floor_ranges_and_vary() {
+        let mut rng = SimpleRng::new(8);
+        let mut seen = Vec::new();
+        for _ in 0..40 {
+            let encounter = pick_encounter(NodeType::Battle, 1, &mut rng);
+            assert!(encounter.min_floor <= 1 && encounter.max_floor >= 1);
+            assert!(!encounter_enemies(encounter).is_empty());
+            if !seen.contains(&encounter.id) {
+                seen.push(encounter.id);
+            }
+        }
+        assert!(
+            seen.len() > 1,
+            "floor 1 should roll more than one encounter"
+        );
+
+        let final_boss = pick_encounter(NodeType::Boss, 12, &mut rng);
+        assert_eq!(encounter_enemies(final_boss)[0].id, OVERSTACK_CORE.id);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::event::Event;
+use crate::log::push_event;
+use crate::step_api::{hp2, ActiveRun};
+
+impl ActiveRun {
+    pub(crate) fn barrier(&self, unit_idx: usize) -> f32 {
+        self.runtime_ref(unit_idx).map(|r| r.barrier).unwrap_or(0.0)
+    }
+
+    pub(crate) fn grant_barrier(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        amount: f32,
+        decay: f32,
+        events: &mut Vec<String>,
+    ) {
+        let amount = hp2(amount.max(0.0));
+        let Some(runtime) = self.runtime_mut(dst_idx) else {
+            return;
+        };
+        runtime.barrier = hp2(runtime.barrier + amount);
+        runtime.barrier_decay = decay.max(0.0);
+        let barrier_after = runtime.barrier;
+
+        push_event(
+            events,
+            Event::BarrierGranted {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                amount,
+                barrier_after,
+            },
+        );
+    }
+
+    /// Spends barrier on an incoming hit; returns the part that was absorbed.
+    pub(crate) fn absorb_with_barrier(&mut self, unit_idx: usize, amount: f32) -> f32 {
+        let Some(runtime) = self.runtime_mut(unit_idx) else {
+            return 0.0;
+        };
+        let absorbed = hp2(runtime.barrier.min(amount));
+        runtime.barrier = hp2(runtime.barrier - absorbed);
+        absorbed
+    }
+
+    pub(crate) fn decay_barriers(&mut self, dt: f32) {
+        for unit_idx in 0..self.unit_count() {
+            if let Some(runtime) = self.runtime_mut(unit_idx) {
+                if runtime.barrier > 0.0 {
+                    runtime.barrier = hp2((runtime.barrier - runtime.barrier_decay * dt).max(0.0));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enemy_spec::ROGUE_DRONE;
+    use crate::engine::damage_system::DamageHit;
+    use crate::skill::{DamageType, StatusType};
+    use crate::step_api::tests::run_against;
+
+    #[test]
+    fn barrier_absorbs_hits_before_hp_and_drains_over_time() {
+        let mut run = run_against(1, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.grant_barrier(1, 1, 15.0, 2.0, &mut events);
+        assert_eq!(run.snapshot().enemies[0].barrier, 15.0);
+
+        events.clear();
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut events,
+        );
+        assert!(events[0].contains("\"absorbed\":10") && events[0].contains("\"hp_damage\":0"));
+        assert_eq!(
+            run.snapshot().enemies[0].hp,
+            ROGUE_DRONE.max_hp,
+            "the hit did nothing"
+        );
+
+        events.clear();
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut events,
+        );
+        assert!(events[0].contains("\"absorbed\":5") && events[0].contains("\"hp_damage\":5"));
+        assert_eq!(run.snapshot().enemies[0].hp, ROGUE_DRONE.max_hp - 5.0);
+
+        run.grant_barrier(1, 1, 3.0, 2.0, &mut events);
+        run.tick_statuses(1.0, &mut events);
+        assert_eq!(run.snapshot().enemies[0].barrier, 1.0);
+    }
+
+    #[test]
+    fn status_ticks_spend_barrier_before_hp() {
+        let mut run = run_against(2, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.grant_barrier(1, 1, 50.0, 0.0, &mut events);
+        run.apply_status(0, 1, StatusType::Burn, 1.0, 5.0, 2, 1.0, 0, &mut events);
+
+        events.clear();
+        run.tick_statuses(1.05, &mut events);
+        let tick = events
+            .iter()
+            .find(|line| line.contains("\"kind\":\"StatusTick\""))
+            .expect("Burn should tick");
+        assert!(tick.contains("\"damage_type\":\"fire\""), "{tick}");
+        assert!(
+            tick.contains("\"absorbed\":2") && tick.contains("\"hp_damage\":0"),
+            "{tick}"
+        );
+        assert_eq!(run.snapshot().enemies[0].hp, ROGUE_DRONE.max_hp);
+        assert_eq!(run.snapshot().enemies[0].barrier, 48.0);
+    }
+}
@@ -1,5 +1,5 @@
-use crate::model::{BattleState, Team};
-use crate::skill::{Condition, EffectTarget, StatusType};
+use crate::model::{BattleState, HeroState, Team};
+use crate::skill::{Condition, EffectSpec, EffectTarget, StatusType};
 use crate::step_api::{ActiveRun, ActiveStatus, TriggerContext, UnitRuntime};
 use crate::trait_spec::TriggerType;
 
@@ -72,7 +72,12 @@ impl ActiveRun {
             .max(0.1)
     }
 
-    pub(crate) fn update_status_power_mul(&mut self, unit_idx: usize, status_type: StatusType, mul: f32) {
+    pub(crate) fn update_status_power_mul(
+        &mut self,
+        unit_idx: usize,
+        status_type: StatusType,
+        mul: f32,
+    ) {
         if let Some(runtime) = self.runtime_mut(unit_idx) {
             let entry = runtime.status_power_mult.entry(status_type).or_insert(1.0);
             *entry = entry.max(mul.max(0.1));
@@ -90,6 +95,24 @@ impl ActiveRun {
         }
     }
 
+    /// Stable battle unit id (`Unit::id`) of the unit at `idx`.
+    pub(crate) fn unit_id_for_idx(&self, idx: usize) -> u32 {
+        self.state_ref()
+            .and_then(|s| s.units.get(idx))
+            .map(|u| u.id)
+            .unwrap_or(idx as u32)
+    }
+
+    /// Run party entry behind a hero unit.
+    pub(crate) fn hero_for_unit(&self, unit_idx: usize) -> Option<&HeroState> {
+        let slot = self.state_ref()?.units.get(unit_idx)?.party_slot?;
+        self.run.party.get(slot)
+    }
+
+    pub(crate) fn unit_idx_for_id(&self, unit_id: u32) -> Option<usize> {
+        self.state_ref()?.units.iter().position(|u| u.id == unit_id)
+    }
+
     pub(crate) fn roll_success(&mut self, chance: f32) -> bool {
         let clamped = chance.clamp(0.0, 1.0);
         if clamped <= 0.0 {
@@ -104,16 +127,39 @@ impl ActiveRun {
 
     pub(crate) fn has_status(&self, unit_idx: usize, status_type: StatusType) -> bool {
         self.statuses_ref(unit_idx)
-            .map(|row| row.iter().any(|s| s.status_type == status_type && s.duration > 0.0))
+            .map(|row| {
+                row.iter()
+                    .any(|s| s.status_type == status_type && s.duration > 0.0)
+            })
             .unwrap_or(false)
     }
 
-    pub(crate) fn status_count(&self, unit_idx: usize) -> u32 {
+    /// Total stacks of `status_type`, summed over independent instances.
+    pub(crate) fn status_stacks(&self, unit_idx: usize, status_type: StatusType) -> u32 {
         self.statuses_ref(unit_idx)
-            .map(|row| row.iter().filter(|s| s.duration > 0.0).count() as u32)
+            .map(|row| {
+                row.iter()
+                    .filter(|s| s.status_type == status_type && s.duration > 0.0)
+                    .map(|s| s.stacks)
+                    .sum()
+            })
             .unwrap_or(0)
     }
 
+    /// Distinct active status types; independent instances of one status count once.
+    pub(crate) fn status_count(&self, unit_idx: usize) -> u32 {
+        let Some(row) = self.statuses_ref(unit_idx) else {
+            return 0;
+        };
+        let mut seen: Vec<StatusType> = Vec::new();
+        for status in row.iter().filter(|s| s.duration > 0.0) {
+            if !seen.contains(&status.status_type) {
+                seen.push(status.status_type);
+            }
+        }
+        seen.len() as u32
+    }
+
     pub(crate) fn target_hp_ratio(&self, unit_idx: usize) -> f32 {
         self.state_ref()
             .map(|state| {
@@ -127,7 +173,11 @@ impl ActiveRun {
             .unwrap_or(1.0)
     }
 
-    pub(crate) fn evaluate_condition(&mut self, condition: Condition, context: TriggerContext) -> bool {
+    pub(crate) fn evaluate_condition(
+        &mut self,
+        condition: Condition,
+        context: TriggerContext,
+    ) -> bool {
         match condition {
             Condition::Always => true,
             Condition::SrcIsPlayer => context
@@ -138,12 +188,21 @@ impl ActiveRun {
                 .dst_idx
                 .map(|idx| self.actor_label_for_idx(idx) == "enemy")
                 .unwrap_or(false),
+            Condition::SrcIsOwner => {
+                context.src_idx.is_some() && context.src_idx == context.owner_idx
+            }
+            Condition::DstIsOwner => {
+                context.dst_idx.is_some() && context.dst_idx == context.owner_idx
+            }
             Condition::AppliedStatusIs(status_type) => context.applied_status == Some(status_type),
             Condition::RandomRollBelow(p) => self.roll_success(p),
             Condition::TargetHPBelow(ratio) => context
                 .dst_idx
                 .map(|idx| self.target_hp_ratio(idx) < ratio)
                 .unwrap_or(false),
+            Condition::TargetHPAbove(ratio) => context
+                .dst_idx
+                .is_some_and(|idx| self.target_hp_ratio(idx) > ratio),
             Condition::TargetHasStatus(status_type) => context
                 .dst_idx
                 .map(|idx| self.has_status(idx, status_type))
@@ -152,9 +211,38 @@ impl ActiveRun {
                 .dst_idx
                 .map(|idx| self.status_count(idx) >= n)
                 .unwrap_or(false),
+            Condition::TargetStacks(status_type, cmp) => context
+                .dst_idx
+                .is_some_and(|idx| cmp.matches(self.status_stacks(idx, status_type))),
+            Condition::SrcHPBelow(ratio) => context
+                .src_idx
+                .is_some_and(|idx| self.target_hp_ratio(idx) < ratio),
+            Condition::SrcHPAbove(ratio) => context
+                .src_idx
+                .is_some_and(|idx| self.target_hp_ratio(idx) > ratio),
+            Condition::SrcHasStatus(status_type) => context
+                .src_idx
+                .is_some_and(|idx| self.has_status(idx, status_type)),
+            Condition::SrcStacks(status_type, cmp) => context
+                .src_idx
+                .is_some_and(|idx| cmp.matches(self.status_stacks(idx, status_type))),
+            Condition::TurnNumber(cmp) => context
+                .src_idx
+                .and_then(|idx| self.runtime_ref(idx))
+                .is_some_and(|runtime| cmp.matches(runtime.turns_taken)),
             Condition::All(items) => items
                 .iter()
                 .all(|item| self.evaluate_condition(*item, context)),
+            Condition::Any(items) => items
+                .iter()
+                .any(|item| self.evaluate_condition(*item, context)),
+            Condition::Not(inner) => !self.evaluate_condition(*inner, context),
+            Condition::SkillIs(id) => context.skill.is_some_and(|skill| skill.id == id),
+            Condition::SkillHasTag(tag) => context.skill.is_some_and(|s| s.tags.contains(&tag)),
+            Condition::DamageTypeIs(damage_type) => context.damage_type == Some(damage_type),
+            Condition::DamageAtLeast(min) => context.amount.is_some_and(|amount| amount >= min),
+            Condition::IsCrit => context.crit,
+            Condition::StacksAtLeast(min) => context.stacks.is_some_and(|stacks| stacks >= min),
         }
     }
 
@@ -170,12 +258,22 @@ impl ActiveRun {
         match target {
             EffectTarget::Src => context.src_idx,
             EffectTarget::Dst => context.dst_idx,
-            EffectTarget::Player => self
-                .state_ref()
-                .and_then(|s| s.units.iter().position(|u| u.team == Team::Player)),
+            EffectTarget::Player => self.party_unit_for(context),
+            EffectTarget::Ally => {
+                let side = [context.src_idx, context.owner_idx]
+                    .into_iter()
+                    .flatten()
+                    .next()
+                    .and_then(|idx| self.state_ref().map(|s| s.units[idx].team))?;
+                self.lowest_hp_ally(side)
+            }
             EffectTarget::Enemy => self
                 .state_ref()
-                .and_then(|s| s.units.iter().position(|u| u.team == Team::Enemy && u.is_alive()))
+                .and_then(|s| {
+                    s.units
+                        .iter()
+                        .position(|u| u.team == Team::Enemy && u.is_alive())
+                })
                 .or_else(|| {
                     self.state_ref()
                         .and_then(|s| s.units.iter().position(|u| u.team == Team::Enemy))
@@ -183,6 +281,60 @@ impl ActiveRun {
         }
     }
 
+    /// Unit a skill effect lands on; `None` for effects that only change the caster's runtime.
+    pub(crate) fn effect_landing_idx(
+        &self,
+        effect: EffectSpec,
+        context: TriggerContext,
+    ) -> Option<usize> {
+        match effect {
+            EffectSpec::DealDamage { .. }
+            | EffectSpec::ApplyStatus { .. }
+            | EffectSpec::ConditionalApplyStatus { .. } => context.dst_idx,
+            EffectSpec::AddStatusStacks { target, .. }
+            | EffectSpec::DealPureDamage { target, .. }
+            | EffectSpec::Cleanse { target, .. }
+            | EffectSpec::ConsumeStacks { target, .. }
+            | EffectSpec::GrantImmunity { target, .. }
+            | EffectSpec::Heal { target, .. }
+            | EffectSpec::GrantBarrier { target, .. } => {
+                self.resolve_effect_target(target, context)
+            }
+            EffectSpec::ConditionalDamageAmp { .. }
+            | EffectSpec::SelfBuff { .. }
+            | EffectSpec::AddProcBonus { .. }
+            | EffectSpec::AddResBonus { .. }
+            | EffectSpec::ModifyStatusPower { .. } => None,
+        }
+    }
+
+    /// Hero involved in `context` (src, then dst, then owner); the first living hero otherwise.
+    pub(crate) fn party_unit_for(&self, context: TriggerContext) -> Option<usize> {
+        let state = self.state_ref()?;
+        [context.src_idx, context.dst_idx, context.owner_idx]
+            .into_iter()
+            .flatten()
+            .find(|&idx| state.units.get(idx).map(|u| u.team) == Some(Team::Player))
+            .or_else(|| {
+                state
+                    .units
+                    .iter()
+                    .position(|u| u.team == Team::Player && u.is_alive())
+            })
+            .or_else(|| state.units.iter().position(|u| u.team == Team::Player))
+    }
+
+    pub(crate) fn lowest_hp_ally(&self, team: Team) -> Option<usize> {
+        let state = self.state_ref()?;
+        (0..state.units.len())
+            .filter(|&idx| state.units[idx].team == team && state.units[idx].is_alive())
+            .min_by(|&a, &b| {
+                self.target_hp_ratio(a)
+                    .partial_cmp(&self.target_hp_ratio(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
     pub(crate) fn pick_target_index(&mut self, target_team: Team) -> Option<usize> {
         let state = self.state_ref()?;
         let targets: Vec<usize> = state
@@ -205,3 +357,135 @@ impl ActiveRun {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::enemy_spec::ROGUE_DRONE;
+    use crate::engine::damage_system::DamageHit;
+    use crate::skill::{Comparison, Condition, DamageType, StatusType, EMBER_LASH, FROST_BITE};
+    use crate::step_api::tests::run_against;
+    use crate::step_api::{ActionKind, ActiveRun, ActiveTrait, TriggerContext};
+    use crate::trait_spec::{TriggerType, FLASHPOINT};
+
+    #[test]
+    fn conditions_read_skill_damage_and_stacks_from_the_context() {
+        let mut run = run_against(1, &[&ROGUE_DRONE]);
+        let hit = TriggerContext {
+            amount: Some(24.0),
+            skill: Some(&EMBER_LASH),
+            damage_type: Some(DamageType::Fire),
+            crit: true,
+            ..TriggerContext::new(TriggerType::OnDamageDealt, Some(0), Some(1))
+        };
+        for (condition, expected) in [
+            (Condition::SkillIs(EMBER_LASH.id), true),
+            (Condition::SkillIs(FROST_BITE.id), false),
+            (Condition::SkillHasTag("fire"), true),
+            (Condition::SkillHasTag("ice"), false),
+            (Condition::DamageTypeIs(DamageType::Fire), true),
+            (Condition::DamageAtLeast(20.0), true),
+            (Condition::DamageAtLeast(30.0), false),
+            (Condition::IsCrit, true),
+            (Condition::StacksAtLeast(1), false),
+        ] {
+            assert_eq!(
+                run.evaluate_condition(condition, hit),
+                expected,
+                "{condition:?}"
+            );
+        }
+
+        let applied = TriggerContext {
+            applied_status: Some(StatusType::Burn),
+            stacks: Some(3),
+            ..TriggerContext::new(TriggerType::OnStatusApplied, Some(0), Some(1))
+        };
+        assert!(run.evaluate_condition(Condition::StacksAtLeast(3), applied));
+        assert!(!run.evaluate_condition(Condition::StacksAtLeast(4), applied));
+        assert!(!run.evaluate_condition(Condition::SkillHasTag("fire"), applied));
+    }
+
+    #[test]
+    fn triggers_fired_while_resolving_a_skill_carry_it() {
+        let mut run = run_against(2, &[&ROGUE_DRONE]);
+        run.active_traits = vec![ActiveTrait::new(FLASHPOINT.id)];
+        let hit = || DamageHit::new(0, 1, "test", 25.0, DamageType::Fire);
+
+        run.apply_damage(hit(), 0, &mut Vec::new());
+        assert!(
+            !run.has_status(1, StatusType::Break),
+            "no skill, no fire tag"
+        );
+
+        run.resolving_skill = Some(&EMBER_LASH);
+        run.apply_damage(hit(), 0, &mut Vec::new());
+        assert!(run.has_status(1, StatusType::Break));
+    }
+
+    #[test]
+    fn boolean_source_and_stack_conditions() {
+        const BURNING: Condition = Condition::TargetHasStatus(StatusType::Burn);
+        const FROZEN: Condition = Condition::TargetHasStatus(StatusType::Freeze);
+
+        let mut run = run_against(3, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.apply_status(0, 0, StatusType::Might, 1.0, 5.0, 2, 1.0, 0, &mut events);
+        run.apply_status(0, 1, StatusType::Burn, 1.0, 5.0, 3, 1.0, 0, &mut events);
+        if let Some(state) = run.state_mut() {
+            state.units[0].hp = state.units[0].max_hp * 0.4;
+        }
+        let context = TriggerContext::new(TriggerType::OnActionUsed, Some(0), Some(1));
+        for (condition, expected) in [
+            (Condition::Any(&[FROZEN, BURNING]), true),
+            (Condition::Any(&[FROZEN]), false),
+            (Condition::Not(&FROZEN), true),
+            (Condition::Not(&Condition::Any(&[FROZEN, BURNING])), false),
+            (Condition::SrcHasStatus(StatusType::Might), true),
+            (Condition::SrcHasStatus(StatusType::Burn), false),
+            (Condition::SrcHPBelow(0.5), true),
+            (Condition::SrcHPAbove(0.5), false),
+            (Condition::TargetHPAbove(0.9), true),
+            (
+                Condition::TargetStacks(StatusType::Burn, Comparison::Exactly(3)),
+                true,
+            ),
+            (
+                Condition::TargetStacks(StatusType::Burn, Comparison::AtMost(2)),
+                false,
+            ),
+            (
+                Condition::SrcStacks(StatusType::Might, Comparison::AtLeast(2)),
+                true,
+            ),
+            (Condition::TurnNumber(Comparison::Exactly(0)), true),
+        ] {
+            assert_eq!(
+                run.evaluate_condition(condition, context),
+                expected,
+                "{condition:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn turn_number_counts_the_turns_a_unit_started() {
+        let mut run = ActiveRun::new(7, 1);
+        let hero_turns = |run: &ActiveRun| run.runtime_ref(0).map(|r| r.turns_taken).unwrap_or(0);
+        for _ in 0..200 {
+            if hero_turns(&run) >= 2 {
+                break;
+            }
+            if run.step_once(0.15, None).need_input {
+                run.step_once(0.0, Some(ActionKind::BasicAttack));
+            }
+        }
+        let turns = hero_turns(&run);
+        assert!(turns >= 2, "hero should start two turns");
+
+        let context = TriggerContext::new(TriggerType::OnTurnStart, Some(0), Some(0));
+        let exact = Condition::TurnNumber(Comparison::Exactly(turns));
+        assert!(run.evaluate_condition(exact, context));
+        let later = Condition::TurnNumber(Comparison::AtLeast(turns + 1));
+        assert!(!run.evaluate_condition(later, context));
+    }
+}
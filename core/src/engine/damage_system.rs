@@ -0,0 +1,597 @@
+use crate::event::Event;
+use crate::log::push_event;
+use crate::skill::{
+    Condition, DamageModifier, DamageSide, DamageStage, DamageType, EffectSpec, StatType,
+};
+use crate::status_spec::status_spec;
+use crate::step_api::{hp2, ActiveRun, TriggerContext};
+use crate::trait_spec::{TriggerType, HP_THRESHOLDS};
+
+/// Stages that take registered modifiers, in pipeline order.
+const MODIFIER_STAGES: [DamageStage; 4] = [
+    DamageStage::Additive,
+    DamageStage::Multiplicative,
+    DamageStage::Crit,
+    DamageStage::Mitigation,
+];
+
+/// One line of a damage breakdown: what `source` did at `stage` and the running amount
+/// after it. `value` is the flat amount for additive steps, otherwise the `1 + value` scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageStep {
+    pub stage: DamageStage,
+    pub source: &'static str,
+    pub value: f32,
+    pub amount: f32,
+}
+
+/// A hit entering the pipeline. `bonuses` are modifiers the caller already resolved
+/// (skill amps and flat bonuses); trait and status modifiers are gathered on the way.
+#[derive(Clone, Debug)]
+pub(crate) struct DamageHit {
+    pub src_idx: usize,
+    pub dst_idx: usize,
+    pub source: &'static str,
+    pub base: f32,
+    pub damage_type: DamageType,
+    pub crit: bool,
+    pub bonuses: Vec<(DamageStage, &'static str, f32)>,
+}
+
+impl DamageHit {
+    pub(crate) fn new(
+        src_idx: usize,
+        dst_idx: usize,
+        source: &'static str,
+        base: f32,
+        damage_type: DamageType,
+    ) -> Self {
+        Self {
+            src_idx,
+            dst_idx,
+            source,
+            base,
+            damage_type,
+            crit: false,
+            bonuses: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_crit(mut self, crit: bool) -> Self {
+        self.crit = crit;
+        self
+    }
+
+    /// Adds a resolved modifier; zero values are dropped so they don't clutter the breakdown.
+    pub(crate) fn with_bonus(
+        mut self,
+        stage: DamageStage,
+        source: &'static str,
+        value: f32,
+    ) -> Self {
+        if value != 0.0 {
+            self.bonuses.push((stage, source, value));
+        }
+        self
+    }
+}
+
+/// Result of running a hit through every stage.
+#[derive(Clone, Debug)]
+pub(crate) struct DamageBreakdown {
+    pub damage_type: DamageType,
+    pub crit: bool,
+    pub steps: Vec<DamageStep>,
+    /// Damage of the hit after mitigation; what `DamageDealt.amount` reports.
+    pub dealt: f32,
+    pub absorbed: f32,
+    pub hp_damage: f32,
+}
+
+impl DamageBreakdown {
+    /// Running amount once `stage` has been applied.
+    #[cfg(test)]
+    pub(crate) fn amount_after(&self, stage: DamageStage) -> f32 {
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| step.stage <= stage)
+            .map(|step| step.amount)
+            .unwrap_or(0.0)
+    }
+}
+
+impl ActiveRun {
+    /// Modifiers registered by statuses (scaled by stacks) and traits of the attacker for
+    /// dealt damage and of the target for taken damage, whose conditions hold for this hit.
+    /// Trait `ConditionalDamageAmp` effects of `OnDamageDealt` (attacker) and `OnDamageTaken`
+    /// (target) rules register as multiplicative modifiers gated by the rule's condition.
+    fn registered_damage_modifiers(
+        &mut self,
+        hit: &DamageHit,
+    ) -> Vec<(DamageStage, &'static str, f32)> {
+        let (src_idx, dst_idx) = (hit.src_idx, hit.dst_idx);
+        // (holder, source, modifier, scale, rule condition)
+        let mut registered: Vec<(usize, &'static str, DamageModifier, f32, Condition)> = Vec::new();
+        for (holder_idx, side) in [(src_idx, DamageSide::Dealt), (dst_idx, DamageSide::Taken)] {
+            for status in self.statuses_ref(holder_idx).into_iter().flatten() {
+                if status.duration <= 0.0 {
+                    continue;
+                }
+                let spec = status_spec(status.status_type);
+                for modifier in spec.damage_modifiers.iter().filter(|m| m.side == side) {
+                    registered.push((
+                        holder_idx,
+                        spec.name,
+                        *modifier,
+                        status.stacks.max(1) as f32,
+                        Condition::Always,
+                    ));
+                }
+            }
+            let amp_trigger = match side {
+                DamageSide::Dealt => TriggerType::OnDamageDealt,
+                DamageSide::Taken => TriggerType::OnDamageTaken,
+            };
+            for (spec, level) in self.unit_trait_specs(holder_idx) {
+                let factor = spec.level_factor(level);
+                for modifier in spec.damage_modifiers.iter().filter(|m| m.side == side) {
+                    registered.push((holder_idx, spec.name, *modifier, factor, Condition::Always));
+                }
+                for rule in spec.triggers.iter().filter(|r| r.trigger == amp_trigger) {
+                    for effect in rule.effects {
+                        if let EffectSpec::ConditionalDamageAmp { condition, amp } =
+                            effect.scaled(factor)
+                        {
+                            let modifier = DamageModifier {
+                                stage: DamageStage::Multiplicative,
+                                side,
+                                condition,
+                                value: amp - 1.0,
+                            };
+                            registered.push((holder_idx, spec.name, modifier, 1.0, rule.condition));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut active = Vec::new();
+        for (holder_idx, source, modifier, scale, gate) in registered {
+            let context = TriggerContext {
+                owner_idx: Some(holder_idx),
+                skill: self.resolving_skill,
+                damage_type: Some(hit.damage_type),
+                crit: hit.crit,
+                ..TriggerContext::new(TriggerType::OnDamageDealt, Some(src_idx), Some(dst_idx))
+            };
+            if self.evaluate_condition(gate, context)
+                && self.evaluate_condition(modifier.condition, context)
+            {
+                active.push((modifier.stage, source, modifier.value * scale));
+            }
+        }
+        active
+    }
+
+    /// Runs `hit` through base, additive, multiplicative, crit, mitigation, barrier and
+    /// final stages. Reads the target's barrier but does not spend it.
+    pub(crate) fn damage_breakdown(&mut self, hit: &DamageHit) -> DamageBreakdown {
+        let mut modifiers = hit.bonuses.clone();
+        modifiers.extend(self.registered_damage_modifiers(hit));
+
+        let mut amount = hit.base.max(0.0);
+        let mut steps = vec![DamageStep {
+            stage: DamageStage::Base,
+            source: hit.source,
+            value: amount,
+            amount,
+        }];
+        for stage in MODIFIER_STAGES {
+            match stage {
+                DamageStage::Crit if !hit.crit => continue,
+                DamageStage::Crit => {
+                    let crit_damage = self
+                        .effective_stat(hit.src_idx, StatType::CritDamage)
+                        .max(1.0);
+                    amount *= crit_damage;
+                    steps.push(DamageStep {
+                        stage,
+                        source: "Crit",
+                        value: crit_damage - 1.0,
+                        amount,
+                    });
+                }
+                DamageStage::Mitigation => {
+                    // Offense never cuts a hit below 0.01; defenses may cancel it outright.
+                    amount = amount.max(0.01);
+                    let mult = self.mitigation_multiplier(hit.dst_idx, hit.damage_type);
+                    if mult != 1.0 {
+                        amount *= mult;
+                        steps.push(DamageStep {
+                            stage,
+                            source: "Defenses",
+                            value: mult - 1.0,
+                            amount,
+                        });
+                    }
+                }
+                _ => {}
+            }
+            for &(_, source, value) in modifiers.iter().filter(|(s, _, _)| *s == stage) {
+                if stage == DamageStage::Additive {
+                    amount += value;
+                } else {
+                    amount *= (1.0 + value).max(0.0);
+                }
+                steps.push(DamageStep {
+                    stage,
+                    source,
+                    value,
+                    amount,
+                });
+            }
+        }
+
+        let dealt = hp2(amount);
+        let absorbed = hp2(self.barrier(hit.dst_idx).min(dealt));
+        if absorbed > 0.0 {
+            steps.push(DamageStep {
+                stage: DamageStage::Barrier,
+                source: "Barrier",
+                value: -absorbed,
+                amount: hp2(dealt - absorbed),
+            });
+        }
+        let hp_damage = hp2(dealt - absorbed);
+        steps.push(DamageStep {
+            stage: DamageStage::Final,
+            source: "HP",
+            value: hp_damage,
+            amount: hp_damage,
+        });
+
+        DamageBreakdown {
+            damage_type: hit.damage_type,
+            crit: hit.crit,
+            steps,
+            dealt,
+            absorbed,
+            hp_damage,
+        }
+    }
+
+    /// Runs `hit` through the pipeline, spends the target's barrier and lowers its HP.
+    /// Returns the breakdown with the target's HP before and after.
+    pub(crate) fn land_damage(&mut self, hit: &DamageHit) -> (DamageBreakdown, (f32, f32)) {
+        let breakdown = self.damage_breakdown(hit);
+        self.absorb_with_barrier(hit.dst_idx, breakdown.absorbed);
+        let mut hp_change = (0.0, 0.0);
+        if let Some(state) = self.state_mut() {
+            let unit = &mut state.units[hit.dst_idx];
+            let hp_before = unit.hp;
+            unit.hp = hp2((unit.hp - breakdown.hp_damage).max(0.0));
+            hp_change = (hp_before, unit.hp);
+        }
+        (breakdown, hp_change)
+    }
+
+    /// Lands a hit: spends barrier, lowers HP, reports `DamageDealt` with its breakdown and
+    /// fires lifesteal, `OnDamageDealt` and, for crits, `OnCrit`. Returns the damage dealt.
+    pub(crate) fn apply_damage(
+        &mut self,
+        hit: DamageHit,
+        trait_depth: u8,
+        events: &mut Vec<String>,
+    ) -> f32 {
+        let (src_idx, dst_idx) = (hit.src_idx, hit.dst_idx);
+        let (breakdown, hp_change) = self.land_damage(&hit);
+        let dealt = breakdown.dealt;
+        let dst_hp_after = hp_change.1;
+
+        push_event(
+            events,
+            Event::DamageDealt {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                damage_type: breakdown.damage_type.as_str(),
+                amount: dealt,
+                absorbed: breakdown.absorbed,
+                hp_damage: breakdown.hp_damage,
+                dst_hp_after,
+                crit: breakdown.crit,
+                miss: false,
+                breakdown: breakdown.steps,
+            },
+        );
+
+        let context = TriggerContext {
+            amount: Some(dealt),
+            damage_type: Some(breakdown.damage_type),
+            crit: breakdown.crit,
+            ..TriggerContext::new(TriggerType::OnDamageDealt, Some(src_idx), Some(dst_idx))
+        };
+        self.apply_lifesteal(src_idx, dst_idx, dealt, trait_depth, events);
+        self.process_trait_triggers(context, trait_depth + 1, events);
+        if breakdown.crit {
+            let context = TriggerContext {
+                trigger_type: TriggerType::OnCrit,
+                ..context
+            };
+            self.process_trait_triggers(context, trait_depth + 1, events);
+        }
+        self.emit_damage_taken_triggers(context, hp_change, trait_depth, events);
+        dealt
+    }
+
+    /// Reactive triggers for the target of the hit or tick described by `damage`:
+    /// `OnDamageTaken`, `OnHpThreshold` for every threshold crossed by `hp_change`
+    /// (before, after) and `OnKill`. Units that were already down trigger nothing.
+    pub(crate) fn emit_damage_taken_triggers(
+        &mut self,
+        damage: TriggerContext,
+        hp_change: (f32, f32),
+        trait_depth: u8,
+        events: &mut Vec<String>,
+    ) {
+        let (hp_before, hp_after) = hp_change;
+        let Some(dst_idx) = damage.dst_idx else {
+            return;
+        };
+        let Some(max_hp) = self.state_ref().map(|s| s.units[dst_idx].max_hp.max(1.0)) else {
+            return;
+        };
+        if hp_before <= 0.0 {
+            return;
+        }
+
+        let context = TriggerContext {
+            trigger_type: TriggerType::OnDamageTaken,
+            ..damage
+        };
+        self.process_trait_triggers(context, trait_depth + 1, events);
+
+        for threshold in HP_THRESHOLDS {
+            if hp_before / max_hp > threshold && hp_after / max_hp <= threshold {
+                let context = TriggerContext {
+                    trigger_type: TriggerType::OnHpThreshold,
+                    amount: Some(threshold),
+                    ..context
+                };
+                self.process_trait_triggers(context, trait_depth + 1, events);
+            }
+        }
+
+        if hp_after <= 0.0 {
+            self.retarget_enemy_intents(dst_idx, events);
+            let context = TriggerContext {
+                trigger_type: TriggerType::OnKill,
+                ..context
+            };
+            self.process_trait_triggers(context, trait_depth + 1, events);
+        }
+    }
+
+    /// Reports a hit that failed its accuracy roll as a zero-damage `DamageDealt`.
+    pub(crate) fn emit_miss(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        damage_type: DamageType,
+        events: &mut Vec<String>,
+    ) {
+        let dst_hp_after = self.state_ref().map(|s| s.units[dst_idx].hp).unwrap_or(0.0);
+        push_event(
+            events,
+            Event::DamageDealt {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                damage_type: damage_type.as_str(),
+                amount: 0.0,
+                absorbed: 0.0,
+                hp_damage: 0.0,
+                dst_hp_after,
+                crit: false,
+                miss: true,
+                breakdown: Vec::new(),
+            },
+        );
+
+        let context = TriggerContext {
+            damage_type: Some(damage_type),
+            ..TriggerContext::new(TriggerType::OnMiss, Some(src_idx), Some(dst_idx))
+        };
+        self.process_trait_triggers(context, 0, events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DamageHit;
+    use crate::enemy_spec::ROGUE_DRONE;
+    use crate::skill::{Condition, DamageStage, DamageType, EffectSpec, StatType, StatusType};
+    use crate::step_api::tests::run_against;
+    use crate::step_api::ActiveTrait;
+    use crate::trait_spec::{
+        TraitRarity, TraitSpec, TriggerRule, TriggerType, BLOODTHIRST, EXECUTIONER, HEMORRHAGE,
+        LAST_STAND,
+    };
+
+    const THICK_HIDE_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::SelfBuff {
+        stat: StatType::Attack,
+        amount: 1.0,
+        duration: 5.0,
+    }];
+    const THICK_HIDE_RULES: [TriggerRule; 1] = [TriggerRule {
+        trigger: TriggerType::OnDamageTaken,
+        condition: Condition::DstIsOwner,
+        effects: &THICK_HIDE_RULE_EFFECTS,
+    }];
+    /// Buffs the hero that was hit, while the trigger's source is the attacker.
+    const THICK_HIDE: TraitSpec = TraitSpec {
+        id: "thick_hide",
+        name: "Thick Hide",
+        description: "Taking damage grants Might.",
+        triggers: &THICK_HIDE_RULES,
+        stat_modifiers: &[],
+        damage_modifiers: &[],
+        rarity: TraitRarity::Common,
+        max_level: 1,
+        level_scaling: 0.0,
+    };
+
+    fn triggered(events: &[String], trigger: &str) -> usize {
+        let needle = format!("\"trigger_type\":\"{trigger}\"");
+        events
+            .iter()
+            .filter(|line| line.contains("\"kind\":\"TraitTriggered\"") && line.contains(&needle))
+            .count()
+    }
+
+    #[test]
+    fn breakdown_runs_every_stage_in_order() {
+        let mut run = run_against(1, &[&ROGUE_DRONE]);
+        run.active_traits = vec![ActiveTrait::new(EXECUTIONER.id)];
+        if let Some(state) = run.state_mut() {
+            state.units[1].hp = ROGUE_DRONE.max_hp * 0.3;
+        }
+        let mut events = Vec::new();
+        run.apply_status(0, 1, StatusType::Break, 1.0, 5.0, 2, 1.0, 0, &mut events);
+        run.grant_barrier(0, 1, 5.0, 0.0, &mut events);
+
+        let hit = DamageHit::new(0, 1, "Strike", 20.0, DamageType::Physical)
+            .with_crit(true)
+            .with_bonus(DamageStage::Additive, "Strike", 4.0);
+        let breakdown = run.damage_breakdown(&hit);
+        let sources: Vec<(&str, &str)> = breakdown
+            .steps
+            .iter()
+            .map(|step| (step.stage.as_str(), step.source))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("base", "Strike"),
+                ("additive", "Strike"),
+                ("multiplicative", "Executioner"),
+                ("multiplicative", "Break"),
+                ("crit", "Crit"),
+                ("mitigation", "Defenses"),
+                ("barrier", "Barrier"),
+                ("final", "HP"),
+            ]
+        );
+        // 24 * 1.3 * 1.2 * 1.5 crit * 100/115 armor
+        assert!((breakdown.amount_after(DamageStage::Crit) - 56.16).abs() < 1e-3);
+        assert_eq!(breakdown.dealt, 48.83);
+        assert_eq!((breakdown.absorbed, breakdown.hp_damage), (5.0, 43.83));
+        assert_eq!(run.barrier(1), 5.0, "computing a breakdown spends nothing");
+
+        events.clear();
+        assert_eq!(run.apply_damage(hit, 0, &mut events), 48.83);
+        assert_eq!(run.barrier(1), 0.0);
+        let line = events
+            .iter()
+            .find(|line| line.contains("\"kind\":\"DamageDealt\""))
+            .unwrap();
+        assert!(line.contains(r#"{"stage":"final","source":"HP","value":43.83,"amount":43.83}"#));
+    }
+
+    #[test]
+    fn only_defenses_cancel_a_hit() {
+        let mut run = run_against(1, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.grant_barrier(0, 1, 5.0, 0.0, &mut events);
+
+        let hit = DamageHit::new(0, 1, "test", 10.0, DamageType::Pure).with_bonus(
+            DamageStage::Multiplicative,
+            "test",
+            -1.0,
+        );
+        assert_eq!(run.damage_breakdown(&hit).dealt, 0.01);
+
+        let hit = DamageHit::new(0, 1, "test", 10.0, DamageType::Pure).with_bonus(
+            DamageStage::Mitigation,
+            "test",
+            -1.0,
+        );
+        let breakdown = run.damage_breakdown(&hit);
+        assert_eq!(
+            (breakdown.dealt, breakdown.absorbed, breakdown.hp_damage),
+            (0.0, 0.0, 0.0)
+        );
+        assert_eq!(run.apply_damage(hit, 0, &mut events), 0.0);
+        assert_eq!(run.barrier(1), 5.0);
+    }
+
+    #[test]
+    fn trait_follow_up_hits_keep_the_triggering_damage_type() {
+        let mut run = run_against(3, &[&ROGUE_DRONE]);
+        run.active_traits = vec![ActiveTrait::new(HEMORRHAGE.id)];
+        let mut events = Vec::new();
+        run.apply_status(0, 1, StatusType::Bleed, 1.0, 5.0, 1, 1.0, 0, &mut events);
+
+        events.clear();
+        let hit = DamageHit::new(0, 1, "Ember Lash", 10.0, DamageType::Fire);
+        run.apply_damage(hit, 0, &mut events);
+        let follow_up = events
+            .iter()
+            .find(|line| line.contains(r#""stage":"base","source":"Hemorrhage""#))
+            .expect("Hemorrhage should add a hit");
+        assert!(follow_up.contains(r#""damage_type":"fire""#), "{follow_up}");
+    }
+
+    #[test]
+    fn losing_hp_fires_threshold_and_kill_triggers() {
+        let mut run = run_against(2, &[&ROGUE_DRONE]);
+        run.active_traits = vec![
+            ActiveTrait::new(LAST_STAND.id),
+            ActiveTrait::new(BLOODTHIRST.id),
+        ];
+        let max_hp = run.snapshot().party[0].max_hp;
+
+        let mut events = Vec::new();
+        let hit = DamageHit::new(1, 0, "test", max_hp * 0.55, DamageType::Pure);
+        run.apply_damage(hit, 0, &mut events);
+        assert_eq!(triggered(&events, "OnHpThreshold"), 1, "crossed 50%");
+        assert_eq!(run.barrier(0), 20.0);
+
+        let hit = DamageHit::new(1, 0, "test", max_hp * 0.4, DamageType::Pure);
+        run.apply_damage(hit, 0, &mut events);
+        assert_eq!(
+            triggered(&events, "OnHpThreshold"),
+            2,
+            "crossed 25% through the barrier"
+        );
+
+        let hit = DamageHit::new(0, 1, "test", ROGUE_DRONE.max_hp, DamageType::Pure);
+        run.apply_damage(hit, 0, &mut events);
+        assert_eq!(
+            triggered(&events, "OnHpThreshold"),
+            2,
+            "enemy thresholds ignore Last Stand"
+        );
+        assert_eq!(triggered(&events, "OnKill"), 1);
+        assert!(run.has_status(0, StatusType::Might));
+    }
+
+    #[test]
+    fn self_buffs_from_damage_taken_land_on_the_trait_owner() {
+        let mut run = run_against(2, &[&ROGUE_DRONE]);
+        run.injected_traits = vec![&THICK_HIDE];
+
+        let mut events = Vec::new();
+        let hit = DamageHit::new(1, 0, "test", 5.0, DamageType::Pure);
+        run.apply_damage(hit, 0, &mut events);
+        assert_eq!(triggered(&events, "OnDamageTaken"), 1);
+        assert!(
+            run.has_status(0, StatusType::Might),
+            "the hit hero owns the trait"
+        );
+        assert!(!run.has_status(1, StatusType::Might), "not the attacker");
+    }
+}
@@ -0,0 +1,223 @@
+use crate::enemy_spec::{enemy_by_id, EnemyPolicy, EnemySpec};
+use crate::event::Event;
+use crate::log::push_event;
+use crate::model::Team;
+use crate::skill::{skill_by_id, EffectSpec, SkillId, SkillSpec, StatType, BASIC_ATTACK};
+use crate::step_api::{hp2, ActiveRun, EnemyIntent, TriggerContext};
+use crate::trait_spec::TriggerType;
+
+impl ActiveRun {
+    pub(crate) fn enemy_spec_for(&self, unit_idx: usize) -> Option<&'static EnemySpec> {
+        self.state_ref()
+            .and_then(|s| s.units.get(unit_idx))
+            .and_then(|u| u.enemy_id)
+            .and_then(enemy_by_id)
+    }
+
+    fn enemy_skill_ready(&self, actor_idx: usize, skill_id: SkillId) -> bool {
+        skill_by_id(skill_id).is_some_and(|skill| self.is_skill_ready(actor_idx, skill))
+    }
+
+    /// Picks the skill an enemy uses against `target_idx` according to its bestiary policy.
+    /// Skills on cooldown or beyond the enemy's energy are skipped; units without a spec,
+    /// loadout or any ready skill fall back to the basic attack.
+    pub(crate) fn choose_enemy_skill(
+        &mut self,
+        actor_idx: usize,
+        target_idx: usize,
+    ) -> &'static SkillSpec {
+        let Some(spec) = self.enemy_spec_for(actor_idx) else {
+            return &BASIC_ATTACK;
+        };
+        let Some(fallback) = spec
+            .skills
+            .iter()
+            .copied()
+            .find(|id| self.enemy_skill_ready(actor_idx, id))
+        else {
+            return &BASIC_ATTACK;
+        };
+
+        let skill_id: SkillId = match spec.policy {
+            EnemyPolicy::WeightedRandom(weights) => {
+                let ready: Vec<bool> = spec
+                    .skills
+                    .iter()
+                    .map(|id| self.enemy_skill_ready(actor_idx, id))
+                    .collect();
+                let weight_at = |i: usize| {
+                    if ready[i] {
+                        weights.get(i).copied().unwrap_or(0)
+                    } else {
+                        0
+                    }
+                };
+                let total: u32 = (0..spec.skills.len()).map(weight_at).sum();
+                if total == 0 {
+                    fallback
+                } else {
+                    let mut roll = self.run.rng.range_usize(total as usize) as u32;
+                    let mut picked = fallback;
+                    for (i, id) in spec.skills.iter().enumerate() {
+                        if roll < weight_at(i) {
+                            picked = id;
+                            break;
+                        }
+                        roll -= weight_at(i);
+                    }
+                    picked
+                }
+            }
+            EnemyPolicy::Rotation => {
+                let len = spec.skills.len();
+                let start = self
+                    .runtime_ref(actor_idx)
+                    .map(|r| r.rotation_cursor)
+                    .unwrap_or(0);
+                let cursor = (start..start + len)
+                    .find(|i| self.enemy_skill_ready(actor_idx, spec.skills[i % len]))
+                    .unwrap_or(start);
+                if let Some(runtime) = self.runtime_mut(actor_idx) {
+                    runtime.rotation_cursor = (cursor + 1) % len;
+                }
+                spec.skills[cursor % len]
+            }
+            EnemyPolicy::Priority(rules) => {
+                let trigger_type = TriggerType::OnActionUsed;
+                let context = TriggerContext {
+                    owner_idx: Some(actor_idx),
+                    ..TriggerContext::new(trigger_type, Some(actor_idx), Some(target_idx))
+                };
+                rules
+                    .iter()
+                    .find(|rule| {
+                        self.enemy_skill_ready(actor_idx, rule.skill)
+                            && self.evaluate_condition(rule.condition, context)
+                    })
+                    .map(|rule| rule.skill)
+                    .unwrap_or(fallback)
+            }
+        };
+
+        skill_by_id(skill_id).unwrap_or(&BASIC_ATTACK)
+    }
+
+    /// Raw damage of the skill's `DealDamage` effects, before conditional amps.
+    pub(crate) fn estimate_skill_damage(&self, actor_idx: usize, skill: &SkillSpec) -> f32 {
+        let atk = self.effective_stat(actor_idx, StatType::Attack);
+        let total: f32 = skill
+            .effects
+            .iter()
+            .map(|effect| match *effect {
+                EffectSpec::DealDamage { multiplier, flat } => {
+                    (atk * skill.base_damage_multiplier * multiplier
+                        + skill.flat_bonus_damage.unwrap_or(0.0)
+                        + flat)
+                        .max(0.01)
+                }
+                _ => 0.0,
+            })
+            .sum();
+        hp2(total)
+    }
+
+    /// Commits the enemy's next action and announces it when it differs from the last one.
+    pub(crate) fn plan_enemy_intent(&mut self, actor_idx: usize, events: &mut Vec<String>) {
+        let Some(target_idx) = self.pick_target_index(Team::Player) else {
+            return;
+        };
+        let skill = self.choose_enemy_skill(actor_idx, target_idx);
+        self.set_enemy_intent(actor_idx, EnemyIntent { skill, target_idx }, events);
+    }
+
+    /// Stores `intent`; `IntentChanged` fires unless it repeats the last announced intent.
+    fn set_enemy_intent(
+        &mut self,
+        actor_idx: usize,
+        intent: EnemyIntent,
+        events: &mut Vec<String>,
+    ) {
+        let Some(runtime) = self.runtime_mut(actor_idx) else {
+            return;
+        };
+        runtime.intent = Some(intent);
+        let changed = runtime
+            .announced_intent
+            .replace(intent)
+            .map(|p| p.skill.id != intent.skill.id || p.target_idx != intent.target_idx)
+            .unwrap_or(true);
+        if changed {
+            push_event(
+                events,
+                Event::IntentChanged {
+                    actor: self.actor_label_for_idx(actor_idx),
+                    actor_id: self.unit_id_for_idx(actor_idx),
+                    skill_id: intent.skill.id,
+                    skill_name: intent.skill.name,
+                    target: self.actor_label_for_idx(intent.target_idx),
+                    target_id: self.unit_id_for_idx(intent.target_idx),
+                    estimated_damage: self.estimate_skill_damage(actor_idx, intent.skill),
+                },
+            );
+        }
+    }
+
+    /// Re-aims living enemies' intents at `dead_idx` to another hero, keeping their skill.
+    pub(crate) fn retarget_enemy_intents(&mut self, dead_idx: usize, events: &mut Vec<String>) {
+        for idx in 0..self.unit_count() {
+            let alive = self.state_ref().is_some_and(|s| s.units[idx].is_alive());
+            let Some(intent) = self.runtime_ref(idx).and_then(|r| r.intent) else {
+                continue;
+            };
+            if !alive || intent.target_idx != dead_idx {
+                continue;
+            }
+            let Some(target_idx) = self.pick_target_index(Team::Player) else {
+                return;
+            };
+            self.set_enemy_intent(
+                idx,
+                EnemyIntent {
+                    target_idx,
+                    ..intent
+                },
+                events,
+            );
+        }
+    }
+
+    pub(crate) fn plan_enemy_intents(&mut self, events: &mut Vec<String>) {
+        for idx in 0..self.unit_count() {
+            let is_living_enemy = self
+                .state_ref()
+                .map(|s| s.units[idx].team == Team::Enemy && s.units[idx].is_alive())
+                .unwrap_or(false);
+            if is_living_enemy {
+                self.plan_enemy_intent(idx, events);
+            }
+        }
+    }
+
+    /// The telegraphed action for this turn. A target that died meanwhile is re-picked.
+    pub(crate) fn take_enemy_intent(
+        &mut self,
+        actor_idx: usize,
+        events: &mut Vec<String>,
+    ) -> Option<(usize, &'static SkillSpec)> {
+        if self.runtime_ref(actor_idx).and_then(|r| r.intent).is_none() {
+            self.plan_enemy_intent(actor_idx, events);
+        }
+        let intent = self.runtime_mut(actor_idx)?.intent.take()?;
+        let target_alive = self
+            .state_ref()
+            .and_then(|s| s.units.get(intent.target_idx))
+            .map(|u| u.is_alive() && u.team == Team::Player)
+            .unwrap_or(false);
+        let target_idx = if target_alive {
+            intent.target_idx
+        } else {
+            self.pick_target_index(Team::Player)?
+        };
+        Some((target_idx, intent.skill))
+    }
+}
@@ -0,0 +1,179 @@
+use crate::event::Event;
+use crate::log::push_event;
+use crate::skill::{HealAmount, StatType};
+use crate::step_api::{hp2, ActiveRun, TriggerContext};
+use crate::trait_spec::TriggerType;
+
+impl ActiveRun {
+    /// HP an `EffectSpec::Heal` would restore on `dst_idx`, before the max-HP cap.
+    pub(crate) fn heal_amount_for(&self, amount: HealAmount, dst_idx: usize, damage: f32) -> f32 {
+        match amount {
+            HealAmount::Flat(flat) => flat,
+            HealAmount::MaxHpPercent(ratio) => {
+                let max_hp = self
+                    .state_ref()
+                    .map(|s| s.units[dst_idx].max_hp)
+                    .unwrap_or(0.0);
+                max_hp * ratio
+            }
+            HealAmount::DamagePercent(ratio) => damage * ratio,
+        }
+        .max(0.0)
+    }
+
+    /// Restores up to `amount` HP on a living unit and reports the overflow as overheal.
+    /// Returns the HP actually restored; fallen units cannot be healed mid-battle.
+    pub(crate) fn heal_unit(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        amount: f32,
+        source: &'static str,
+        trait_depth: u8,
+        events: &mut Vec<String>,
+    ) -> f32 {
+        let Some(unit) = self.state_mut().map(|s| &mut s.units[dst_idx]) else {
+            return 0.0;
+        };
+        if !unit.is_alive() || amount <= 0.0 {
+            return 0.0;
+        }
+        let before = unit.hp;
+        unit.hp = hp2((unit.hp + amount).min(unit.max_hp));
+        let healed = hp2(unit.hp - before);
+        let dst_hp_after = unit.hp;
+
+        push_event(
+            events,
+            Event::Healed {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                source,
+                amount: healed,
+                overheal: hp2((amount - healed).max(0.0)),
+                dst_hp_after,
+            },
+        );
+
+        if healed > 0.0 {
+            let context = TriggerContext {
+                amount: Some(healed),
+                ..TriggerContext::new(TriggerType::OnHeal, Some(src_idx), Some(dst_idx))
+            };
+            self.process_trait_triggers(context, trait_depth + 1, events);
+        }
+        healed
+    }
+
+    /// Heals the attacker for its `StatType::Lifesteal` share of a landed hit.
+    pub(crate) fn apply_lifesteal(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        dealt: f32,
+        trait_depth: u8,
+        events: &mut Vec<String>,
+    ) {
+        if src_idx == dst_idx {
+            return;
+        }
+        let ratio = self.effective_stat(src_idx, StatType::Lifesteal);
+        if ratio > 0.0 {
+            self.heal_unit(
+                src_idx,
+                src_idx,
+                dealt * ratio,
+                "Lifesteal",
+                trait_depth,
+                events,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enemy_spec::ROGUE_DRONE;
+    use crate::engine::damage_system::DamageHit;
+    use crate::skill::{DamageType, StatusType};
+    use crate::step_api::tests::run_against;
+    use crate::step_api::{ActiveRun, ActiveTrait};
+    use crate::trait_spec::{SECOND_WIND, VAMPIRIC};
+
+    fn wounded_hero_battle(seed: u64, wound: f32) -> ActiveRun {
+        let mut run = run_against(seed, &[&ROGUE_DRONE]);
+        run.apply_damage(
+            DamageHit::new(1, 0, "test", wound, DamageType::Pure),
+            0,
+            &mut Vec::new(),
+        );
+        run
+    }
+
+    fn healed_events(events: &[String]) -> Vec<&String> {
+        events
+            .iter()
+            .filter(|line| line.contains("\"kind\":\"Healed\""))
+            .collect()
+    }
+
+    #[test]
+    fn heals_cap_at_max_hp_and_report_overheal() {
+        let mut run = wounded_hero_battle(1, 10.0);
+        run.active_traits = vec![ActiveTrait::new(SECOND_WIND.id)];
+
+        let mut events = Vec::new();
+        assert_eq!(run.heal_unit(0, 0, 25.0, "Mend", 0, &mut events), 10.0);
+        let healed = healed_events(&events);
+        assert_eq!(healed.len(), 1);
+        assert!(healed[0].contains("\"amount\":10") && healed[0].contains("\"overheal\":15"));
+        assert!(
+            run.has_status(0, StatusType::Haste),
+            "OnHeal fired Second Wind"
+        );
+    }
+
+    #[test]
+    fn regen_ticks_restore_hp() {
+        let mut run = wounded_hero_battle(2, 10.0);
+        let mut events = Vec::new();
+        run.apply_status(0, 0, StatusType::Regen, 1.0, 4.0, 2, 1.5, 0, &mut events);
+
+        events.clear();
+        run.tick_statuses(1.05, &mut events);
+        let healed = healed_events(&events);
+        assert_eq!(healed.len(), 1);
+        assert!(healed[0].contains("\"source\":\"Regen\"") && healed[0].contains("\"amount\":3"));
+        assert!(!events
+            .iter()
+            .any(|line| line.contains("\"kind\":\"StatusTick\"")));
+    }
+
+    #[test]
+    fn lifesteal_heals_the_attacker_for_a_share_of_the_hit() {
+        let mut run = wounded_hero_battle(3, 10.0);
+        let hp_before = run.snapshot().party[0].hp;
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut Vec::new(),
+        );
+        assert_eq!(
+            run.snapshot().party[0].hp,
+            hp_before,
+            "no lifesteal without a source"
+        );
+
+        run.active_traits = vec![ActiveTrait::new(VAMPIRIC.id)];
+        let mut events = Vec::new();
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut events,
+        );
+        assert!((run.snapshot().party[0].hp - hp_before - 1.5).abs() < 1e-3);
+        assert!(healed_events(&events)[0].contains("\"source\":\"Lifesteal\""));
+    }
+}
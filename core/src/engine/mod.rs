@@ -1,5 +1,13 @@
+pub(crate) mod barrier_system;
 pub(crate) mod combat_state;
+pub(crate) mod damage_system;
+pub(crate) mod enemy_ai;
+pub(crate) mod heal_system;
+pub(crate) mod node_system;
+pub(crate) mod resource_system;
+pub(crate) mod reward_system;
 pub(crate) mod snapshot;
+pub(crate) mod stat_system;
 pub(crate) mod status_system;
 pub(crate) mod trait_system;
 pub(crate) mod turn_system;
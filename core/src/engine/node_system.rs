@@ -0,0 +1,276 @@
+use crate::event::Event;
+use crate::log::push_event;
+use crate::model::{NodeType, RunState};
+use crate::node_spec::{
+    NodeChoiceSpec, NodeEffect, EVENT_TABLE, LEAVE, REST_CHOICES, SHOP_OFFER_COUNT, SHOP_STOCK,
+};
+use crate::rng::SimpleRng;
+use crate::step_api::{hp2, ActiveRun};
+
+/// Choices rolled for one non-combat node.
+pub(crate) struct NodeOffer {
+    pub(crate) node_type: NodeType,
+    pub(crate) title: &'static str,
+    pub(crate) choices: Vec<&'static NodeChoiceSpec>,
+}
+
+impl NodeOffer {
+    pub(crate) fn roll(node_type: NodeType, rng: &mut SimpleRng) -> Option<Self> {
+        match node_type {
+            NodeType::Event => {
+                let spec = EVENT_TABLE[rng.range_usize(EVENT_TABLE.len())];
+                Some(Self {
+                    node_type,
+                    title: spec.name,
+                    choices: spec.choices.iter().collect(),
+                })
+            }
+            NodeType::Rest => Some(Self {
+                node_type,
+                title: "Rest Site",
+                choices: REST_CHOICES.iter().collect(),
+            }),
+            NodeType::Shop => {
+                let mut pool: Vec<&'static NodeChoiceSpec> = SHOP_STOCK.iter().collect();
+                let mut choices = Vec::new();
+                while choices.len() < SHOP_OFFER_COUNT && !pool.is_empty() {
+                    let pick = rng.range_usize(pool.len());
+                    choices.push(pool.remove(pick));
+                }
+                choices.push(&LEAVE);
+                Some(Self {
+                    node_type,
+                    title: "Shop",
+                    choices,
+                })
+            }
+            NodeType::Battle | NodeType::Boss => None,
+        }
+    }
+
+    /// Choice used by the auto-run loop: shops are left untouched, other nodes take option 0.
+    pub(crate) fn default_choice_index(&self) -> usize {
+        if self.node_type == NodeType::Shop {
+            self.choices.len().saturating_sub(1)
+        } else {
+            0
+        }
+    }
+
+    /// Shops stay open until the player leaves; every other node closes on its first choice.
+    pub(crate) fn closes_on(&self, choice: &NodeChoiceSpec) -> bool {
+        self.node_type != NodeType::Shop || choice.id == LEAVE.id
+    }
+
+    pub(crate) fn choice_labels(&self) -> Vec<String> {
+        self.choices
+            .iter()
+            .map(|c| {
+                if c.gold_cost > 0 {
+                    format!("{} ({}g)", c.label, c.gold_cost)
+                } else {
+                    c.label.to_string()
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn gain_gold(run: &mut RunState, amount: u32, events: &mut Vec<String>) {
+    if amount == 0 {
+        return;
+    }
+    run.gold = run.gold.saturating_add(amount);
+    push_event(
+        events,
+        Event::GoldChanged {
+            delta: amount as i32,
+            gold_after: run.gold,
+        },
+    );
+}
+
+/// Pays for one node choice and applies its effects to run-level player state.
+pub(crate) fn apply_node_choice(
+    run: &mut RunState,
+    choice: &'static NodeChoiceSpec,
+    events: &mut Vec<String>,
+) -> Result<(), String> {
+    if run.gold < choice.gold_cost {
+        return Err(format!("insufficient_gold:{}", choice.gold_cost));
+    }
+
+    if choice.gold_cost > 0 {
+        run.gold -= choice.gold_cost;
+        push_event(
+            events,
+            Event::GoldChanged {
+                delta: -(choice.gold_cost as i32),
+                gold_after: run.gold,
+            },
+        );
+    }
+
+    let mut parts = Vec::new();
+    for effect in choice.effects {
+        match *effect {
+            NodeEffect::HealRatio(ratio) => {
+                let healed = run.recover_party(ratio);
+                parts.push(format!("hp +{healed:.2}"));
+            }
+            NodeEffect::LoseHpRatio(ratio) => {
+                let mut lost = 0.0;
+                for hero in run.party.iter_mut().filter(|h| h.hp > 0.0) {
+                    let before = hero.hp;
+                    hero.hp = hp2((hero.hp - hero.max_hp * ratio).max(1.0));
+                    lost += before - hero.hp;
+                }
+                parts.push(format!("hp -{lost:.2}"));
+            }
+            NodeEffect::GainGold(amount) => {
+                gain_gold(run, amount, events);
+                parts.push(format!("gold +{amount}"));
+            }
+            NodeEffect::AddMaxHp(amount) => {
+                for hero in &mut run.party {
+                    hero.max_hp = hp2(hero.max_hp + amount);
+                    // The raise never revives a downed hero.
+                    if hero.hp > 0.0 {
+                        hero.hp = hp2(hero.hp + amount);
+                    }
+                }
+                parts.push(format!("max_hp +{amount:.2}"));
+            }
+            NodeEffect::AddAttack(amount) => {
+                for hero in &mut run.party {
+                    hero.atk += amount;
+                }
+                parts.push(format!("atk +{amount}"));
+            }
+            NodeEffect::AddSpeed(amount) => {
+                for hero in &mut run.party {
+                    hero.speed += amount;
+                }
+                parts.push(format!("speed +{amount:.2}"));
+            }
+        }
+    }
+
+    push_event(
+        events,
+        Event::NodeChoiceMade {
+            choice: choice.label,
+            effect_summary: if parts.is_empty() {
+                "nothing".to_string()
+            } else {
+                parts.join(", ")
+            },
+        },
+    );
+    Ok(())
+}
+
+impl ActiveRun {
+    /// Map nodes reachable from the current position; the start floor before the first node.
+    pub(crate) fn next_node_options(&self) -> Vec<u32> {
+        match self.path.last() {
+            Some(id) => self
+                .map
+                .node(*id)
+                .map(|n| n.next.clone())
+                .unwrap_or_default(),
+            None => self.map.start_ids(),
+        }
+    }
+
+    pub(crate) fn resolve_path_choice(
+        &mut self,
+        node_id: u32,
+        events: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if !self.awaiting_path {
+            return Err("no_pending_path".to_string());
+        }
+        if !self.next_node_options().contains(&node_id) {
+            return Err(format!("invalid_path:{node_id}"));
+        }
+        self.awaiting_path = false;
+        self.enter_node(node_id, events);
+        Ok(())
+    }
+
+    pub(crate) fn open_node_offer(&mut self, node_type: NodeType, events: &mut Vec<String>) {
+        let Some(offer) = NodeOffer::roll(node_type, &mut self.run.rng) else {
+            return;
+        };
+        push_event(
+            events,
+            Event::NodeOffered {
+                title: offer.title,
+                choice_count: offer.choices.len() as u32,
+            },
+        );
+        self.pending_node = Some(offer);
+    }
+
+    pub(crate) fn node_choice_labels(&self) -> Vec<String> {
+        self.pending_node
+            .as_ref()
+            .map(|offer| offer.choice_labels())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn resolve_node_choice(
+        &mut self,
+        index: u32,
+        events: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let Some(offer) = self.pending_node.as_ref() else {
+            return Err("no_pending_node".to_string());
+        };
+        let Some(choice) = offer.choices.get(index as usize).copied() else {
+            return Err(format!("invalid_choice:{index}"));
+        };
+        let closes = offer.closes_on(choice);
+
+        apply_node_choice(&mut self.run, choice, events)?;
+
+        if closes {
+            self.pending_node = None;
+        } else if let Some(offer) = self.pending_node.as_mut() {
+            offer.choices.remove(index as usize);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_node_choice;
+    use crate::hero_spec::ARCANIST;
+    use crate::model::{HeroState, RunState};
+    use crate::node_spec::SHOP_STOCK;
+
+    #[test]
+    fn max_hp_raise_leaves_downed_heroes_down() {
+        let mut run = RunState::new(1);
+        let mut downed = HeroState::from_spec(&ARCANIST);
+        downed.hp = 0.0;
+        run.party.push(downed);
+        run.gold = 35;
+        let (hp, max_hp) = (run.party[0].hp, run.party[0].max_hp);
+
+        let plating = &SHOP_STOCK[1];
+        let mut events = Vec::new();
+        apply_node_choice(&mut run, plating, &mut events).unwrap();
+        assert_eq!(
+            (run.party[0].hp, run.party[0].max_hp),
+            (hp + 15.0, max_hp + 15.0)
+        );
+        assert_eq!(run.party[1].max_hp, ARCANIST.max_hp + 15.0);
+        assert_eq!(
+            run.party[1].hp, 0.0,
+            "the raise must not revive a downed hero"
+        );
+    }
+}
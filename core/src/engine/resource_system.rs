@@ -0,0 +1,60 @@
+use crate::skill::{SkillSpec, ENERGY_PER_TURN, MAX_ENERGY, STARTING_ENERGY};
+use crate::step_api::ActiveRun;
+
+impl ActiveRun {
+    pub(crate) fn unit_energy(&self, unit_idx: usize) -> u32 {
+        self.runtime_ref(unit_idx)
+            .map(|r| r.energy)
+            .unwrap_or(STARTING_ENERGY)
+    }
+
+    pub(crate) fn cooldown_remaining(&self, unit_idx: usize, skill: &SkillSpec) -> u32 {
+        self.runtime_ref(unit_idx)
+            .and_then(|r| r.cooldowns.get(skill.id).copied())
+            .unwrap_or(0)
+    }
+
+    /// Why `unit_idx` cannot use `skill` right now, as an `invalid_action:*` error.
+    pub(crate) fn check_skill_ready(
+        &self,
+        unit_idx: usize,
+        skill: &SkillSpec,
+    ) -> Result<(), String> {
+        let cooldown = self.cooldown_remaining(unit_idx, skill);
+        if cooldown > 0 {
+            return Err(format!(
+                "invalid_action:on_cooldown:{}:{cooldown}",
+                skill.id
+            ));
+        }
+        let energy = self.unit_energy(unit_idx);
+        if energy < skill.energy_cost {
+            return Err(format!(
+                "invalid_action:insufficient_energy:{}:{energy}/{}",
+                skill.id, skill.energy_cost
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_skill_ready(&self, unit_idx: usize, skill: &SkillSpec) -> bool {
+        self.check_skill_ready(unit_idx, skill).is_ok()
+    }
+
+    /// Pays for `skill` and closes the unit's turn: other cooldowns tick down,
+    /// the used skill starts its own cooldown and energy regenerates.
+    pub(crate) fn commit_skill_use(&mut self, unit_idx: usize, skill: &SkillSpec) {
+        let Some(runtime) = self.runtime_mut(unit_idx) else {
+            return;
+        };
+        runtime.energy = runtime.energy.saturating_sub(skill.energy_cost);
+        runtime.cooldowns.retain(|_, turns| {
+            *turns -= 1;
+            *turns > 0
+        });
+        if skill.cooldown > 0 {
+            runtime.cooldowns.insert(skill.id, skill.cooldown);
+        }
+        runtime.energy = (runtime.energy + ENERGY_PER_TURN).min(MAX_ENERGY);
+    }
+}
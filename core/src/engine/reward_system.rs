@@ -0,0 +1,164 @@
+use crate::event::Event;
+use crate::log::push_event;
+use crate::rng::SimpleRng;
+use crate::step_api::{ActiveRun, ActiveTrait};
+use crate::trait_spec::{
+    trait_by_id, TraitId, TraitSpec, MAX_ACTIVE_TRAITS, TRAIT_DRAFT_POOL, TRAIT_OFFER_SIZE,
+};
+
+/// Rolls up to `TRAIT_OFFER_SIZE` distinct draft-pool traits, weighted by rarity. Owned traits
+/// below their max level come back as upgrades; new traits only while under `MAX_ACTIVE_TRAITS`.
+pub(crate) fn roll_trait_offer(owned: &[ActiveTrait], rng: &mut SimpleRng) -> Vec<TraitId> {
+    let room = owned.len() < MAX_ACTIVE_TRAITS;
+    let mut pool: Vec<&'static TraitSpec> = TRAIT_DRAFT_POOL
+        .iter()
+        .filter_map(|id| trait_by_id(id))
+        .filter(|spec| match owned.iter().find(|t| t.id == spec.id) {
+            Some(t) => t.level < spec.max_level,
+            None => room,
+        })
+        .collect();
+    let mut offer = Vec::new();
+    while offer.len() < TRAIT_OFFER_SIZE && !pool.is_empty() {
+        let total: u32 = pool.iter().map(|spec| spec.rarity.draft_weight()).sum();
+        let mut roll = rng.range_usize(total as usize) as u32;
+        let mut pick = 0;
+        for (idx, spec) in pool.iter().enumerate() {
+            let weight = spec.rarity.draft_weight();
+            if roll < weight {
+                pick = idx;
+                break;
+            }
+            roll -= weight;
+        }
+        offer.push(pool.remove(pick).id);
+    }
+    offer
+}
+
+impl ActiveRun {
+    /// Opens the post-victory trait draft; nothing is offered when no trait can be gained.
+    pub(crate) fn open_trait_offer(&mut self, events: &mut Vec<String>) {
+        let offer = roll_trait_offer(&self.active_traits, &mut self.run.rng);
+        if offer.is_empty() {
+            return;
+        }
+        push_event(
+            events,
+            Event::TraitOffered {
+                choice_count: offer.len() as u32,
+            },
+        );
+        self.pending_traits = offer;
+    }
+
+    /// Level the trait would reach if drafted now.
+    fn next_trait_level(&self, id: TraitId) -> u32 {
+        self.active_traits
+            .iter()
+            .find(|t| t.id == id)
+            .map_or(1, |t| t.level + 1)
+    }
+
+    pub(crate) fn trait_choice_labels(&self) -> Vec<String> {
+        self.pending_traits
+            .iter()
+            .filter_map(|id| trait_by_id(id))
+            .map(|t| {
+                format!(
+                    "{} ({}, Lv{}): {}",
+                    t.name,
+                    t.rarity.as_str(),
+                    self.next_trait_level(t.id),
+                    t.description
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn resolve_trait_choice(
+        &mut self,
+        index: u32,
+        events: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if self.pending_traits.is_empty() {
+            return Err("no_pending_traits".to_string());
+        }
+        let Some(spec) = self
+            .pending_traits
+            .get(index as usize)
+            .and_then(|id| trait_by_id(id))
+        else {
+            return Err(format!("invalid_choice:{index}"));
+        };
+        let Some(level) = self.gain_trait(spec) else {
+            return Err(format!("invalid_choice:{index}"));
+        };
+        self.pending_traits.clear();
+        push_event(
+            events,
+            Event::TraitAcquired {
+                trait_name: spec.name,
+                level,
+                trait_count: self.active_traits.len() as u32,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::roll_trait_offer;
+    use crate::rng::SimpleRng;
+    use crate::step_api::ActiveTrait;
+    use crate::trait_spec::{
+        trait_by_id, TraitId, MAX_ACTIVE_TRAITS, TRAIT_DRAFT_POOL, TRAIT_OFFER_SIZE,
+    };
+
+    fn owned_at(ids: &[TraitId], max_level: bool) -> Vec<ActiveTrait> {
+        ids.iter()
+            .map(|id| ActiveTrait {
+                id,
+                level: if max_level {
+                    trait_by_id(id).map_or(1, |t| t.max_level)
+                } else {
+                    1
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trait_offer_is_seeded_and_skips_maxed_traits() {
+        let maxed = owned_at(&TRAIT_DRAFT_POOL[..TRAIT_OFFER_SIZE + 1], true);
+        let first = roll_trait_offer(&maxed, &mut SimpleRng::new(7));
+        let again = roll_trait_offer(&maxed, &mut SimpleRng::new(7));
+
+        assert_eq!(first, again);
+        assert_eq!(first.len(), TRAIT_OFFER_SIZE);
+        assert!(first.iter().all(|id| maxed.iter().all(|t| t.id != *id)));
+        assert!(first
+            .iter()
+            .enumerate()
+            .all(|(i, id)| !first[..i].contains(id)));
+
+        let all_maxed = owned_at(&TRAIT_DRAFT_POOL, true);
+        assert!(roll_trait_offer(&all_maxed, &mut SimpleRng::new(7)).is_empty());
+    }
+
+    #[test]
+    fn trait_offer_at_the_cap_only_upgrades_owned_traits() {
+        let capped = owned_at(&TRAIT_DRAFT_POOL[..MAX_ACTIVE_TRAITS], false);
+        let offer = roll_trait_offer(&capped, &mut SimpleRng::new(3));
+
+        assert!(!offer.is_empty());
+        for id in offer {
+            let owned = capped
+                .iter()
+                .find(|t| t.id == id)
+                .expect("offer should be owned");
+            assert!(trait_by_id(id).is_some_and(|t| owned.level < t.max_level));
+        }
+    }
+}
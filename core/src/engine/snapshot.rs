@@ -1,5 +1,9 @@
 use crate::model::Team;
-use crate::step_api::{ActiveRun, Snapshot, StatusSnapshot, UnitSnapshot};
+use crate::skill::{skill_by_id, SkillId, StatType, MAX_ENERGY, STARTING_ENERGY};
+use crate::step_api::{
+    ActiveRun, IntentSnapshot, MapNodeSnapshot, ResistanceSnapshot, SkillReadinessSnapshot,
+    Snapshot, StatusSnapshot, UnitSnapshot,
+};
 
 impl ActiveRun {
     fn to_status_snapshots(&self, unit_idx: usize) -> Vec<StatusSnapshot> {
@@ -17,76 +21,182 @@ impl ActiveRun {
             .unwrap_or_default()
     }
 
-    pub(crate) fn snapshot(&self) -> Snapshot {
-        if let Some(battle) = &self.current_battle {
-            let player_idx = battle
-                .state
-                .units
-                .iter()
-                .enumerate()
-                .find(|(_, u)| u.team == Team::Player)
-                .map(|(idx, _)| idx)
-                .unwrap_or(0);
+    fn to_resistance_snapshots(&self, unit_idx: usize) -> Vec<ResistanceSnapshot> {
+        self.enemy_spec_for(unit_idx)
+            .map(|spec| {
+                spec.resistances
+                    .iter()
+                    .map(|r| ResistanceSnapshot {
+                        damage_type: r.damage_type.as_str().to_string(),
+                        value: r.value,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-            let enemy_idx = battle
-                .state
-                .units
-                .iter()
-                .enumerate()
-                .find(|(_, u)| u.team == Team::Enemy)
-                .map(|(idx, _)| idx)
-                .unwrap_or(0);
+    fn to_intent_snapshot(&self, unit_idx: usize) -> Option<IntentSnapshot> {
+        let intent = self.runtime_ref(unit_idx)?.intent?;
+        Some(IntentSnapshot {
+            skill_id: intent.skill.id.to_string(),
+            skill_name: intent.skill.name.to_string(),
+            target: self.actor_label_for_idx(intent.target_idx).to_string(),
+            target_id: self.unit_id_for_idx(intent.target_idx),
+            estimated_damage: self.estimate_skill_damage(unit_idx, intent.skill),
+        })
+    }
 
-            let player_unit = &battle.state.units[player_idx];
-            let enemy_unit = &battle.state.units[enemy_idx];
+    /// Readiness of `skills` for `unit_idx`; outside battle every skill is off cooldown.
+    fn to_skill_snapshots(
+        &self,
+        unit_idx: usize,
+        skills: &[SkillId],
+    ) -> Vec<SkillReadinessSnapshot> {
+        skills
+            .iter()
+            .filter_map(|id| skill_by_id(id))
+            .map(|skill| SkillReadinessSnapshot {
+                skill_id: skill.id.to_string(),
+                name: skill.name.to_string(),
+                cooldown_remaining: self.cooldown_remaining(unit_idx, skill),
+                energy_cost: skill.energy_cost,
+                ready: self.is_skill_ready(unit_idx, skill),
+            })
+            .collect()
+    }
+
+    fn run_state_label(&self) -> &'static str {
+        if self.ended {
+            "ended"
+        } else if !self.pending_traits.is_empty() {
+            "trait_choice"
+        } else if self.pending_node.is_some() {
+            "node_choice"
+        } else if self.awaiting_path {
+            "path_choice"
+        } else {
+            "running"
+        }
+    }
 
-            Snapshot {
-                run_state: if self.ended {
-                    "ended".to_string()
-                } else {
-                    "running".to_string()
-                },
-                run_result: self.result.to_string(),
-                node_index: self.node_index,
-                battle_index: self.battle_index,
-                elapsed_time: self.elapsed_time,
-                player: UnitSnapshot {
-                    hp: player_unit.hp,
-                    max_hp: player_unit.max_hp,
-                    action_gauge: player_unit.action_gauge,
-                    statuses: self.to_status_snapshots(player_idx),
-                },
-                enemy: UnitSnapshot {
-                    hp: enemy_unit.hp,
-                    max_hp: enemy_unit.max_hp,
-                    action_gauge: enemy_unit.action_gauge,
-                    statuses: self.to_status_snapshots(enemy_idx),
-                },
+    pub(crate) fn map_snapshot(&self) -> Vec<MapNodeSnapshot> {
+        let current = self.path.last().copied();
+        let selectable = if self.awaiting_path {
+            self.next_node_options()
+        } else {
+            Vec::new()
+        };
+        self.map
+            .nodes
+            .iter()
+            .map(|n| MapNodeSnapshot {
+                id: n.id,
+                act: n.act,
+                floor: n.floor,
+                lane: n.lane,
+                node_type: n.node_type.as_str().to_string(),
+                next: n.next.clone(),
+                visited: self.path.contains(&n.id),
+                current: current == Some(n.id),
+                selectable: selectable.contains(&n.id),
+            })
+            .collect()
+    }
+
+    fn to_unit_snapshot(&self, unit_idx: usize) -> Option<UnitSnapshot> {
+        let unit = self.state_ref()?.units.get(unit_idx)?;
+        let skills = match unit.team {
+            Team::Player => self.hero_for_unit(unit_idx).map(|h| h.skills),
+            Team::Enemy => self.enemy_spec_for(unit_idx).map(|spec| spec.skills),
+        }
+        .unwrap_or(&[]);
+        Some(UnitSnapshot {
+            unit_id: unit.id,
+            name: unit.name.to_string(),
+            hp: unit.hp,
+            max_hp: unit.max_hp,
+            base_atk: self.base_stat(unit_idx, StatType::Attack),
+            atk: self.effective_stat(unit_idx, StatType::Attack),
+            base_speed: self.base_stat(unit_idx, StatType::Speed),
+            speed: self.effective_stat(unit_idx, StatType::Speed),
+            crit_chance: self.effective_stat(unit_idx, StatType::CritChance),
+            crit_damage: self.effective_stat(unit_idx, StatType::CritDamage),
+            accuracy: self.effective_stat(unit_idx, StatType::Accuracy),
+            evasion: self.effective_stat(unit_idx, StatType::Evasion),
+            action_gauge: unit.action_gauge,
+            barrier: self.barrier(unit_idx),
+            armor: self
+                .enemy_spec_for(unit_idx)
+                .map(|e| e.armor)
+                .unwrap_or(0.0),
+            resistances: self.to_resistance_snapshots(unit_idx),
+            statuses: self.to_status_snapshots(unit_idx),
+            intent: self.to_intent_snapshot(unit_idx),
+            energy: self.unit_energy(unit_idx),
+            max_energy: MAX_ENERGY,
+            skills: self.to_skill_snapshots(unit_idx, skills),
+        })
+    }
+
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        let node_type = self
+            .current_node_type()
+            .map(|t| t.as_str().to_string())
+            .unwrap_or_default();
+
+        let mut party = Vec::new();
+        let mut enemies = Vec::new();
+        if let Some(battle) = &self.current_battle {
+            for (idx, unit) in battle.state.units.iter().enumerate() {
+                let target = match unit.team {
+                    Team::Player => &mut party,
+                    Team::Enemy => &mut enemies,
+                };
+                target.extend(self.to_unit_snapshot(idx));
             }
         } else {
-            Snapshot {
-                run_state: if self.ended {
-                    "ended".to_string()
-                } else {
-                    "running".to_string()
-                },
-                run_result: self.result.to_string(),
-                node_index: self.node_index,
-                battle_index: self.battle_index,
-                elapsed_time: self.elapsed_time,
-                player: UnitSnapshot {
-                    hp: self.run.player_hp,
-                    max_hp: self.run.player_max_hp,
-                    action_gauge: 0.0,
-                    statuses: Vec::new(),
-                },
-                enemy: UnitSnapshot {
-                    hp: 0.0,
-                    max_hp: 0.0,
+            party = self
+                .run
+                .party
+                .iter()
+                .enumerate()
+                .map(|(slot, hero)| UnitSnapshot {
+                    unit_id: slot as u32,
+                    name: hero.name.to_string(),
+                    hp: hero.hp,
+                    max_hp: hero.max_hp,
+                    base_atk: hero.atk as f32,
+                    atk: self.hero_stat(hero, StatType::Attack),
+                    base_speed: hero.speed,
+                    speed: self.hero_stat(hero, StatType::Speed),
+                    crit_chance: self.hero_stat(hero, StatType::CritChance),
+                    crit_damage: self.hero_stat(hero, StatType::CritDamage),
+                    accuracy: self.hero_stat(hero, StatType::Accuracy),
+                    evasion: self.hero_stat(hero, StatType::Evasion),
                     action_gauge: 0.0,
+                    barrier: 0.0,
+                    armor: 0.0,
+                    resistances: Vec::new(),
                     statuses: Vec::new(),
-                },
-            }
+                    intent: None,
+                    energy: STARTING_ENERGY,
+                    max_energy: MAX_ENERGY,
+                    skills: self.to_skill_snapshots(slot, hero.skills),
+                })
+                .collect();
+        }
+
+        Snapshot {
+            run_state: self.run_state_label().to_string(),
+            run_result: self.result.to_string(),
+            node_index: self.node_index,
+            node_type,
+            floor: self.run.floor,
+            battle_index: self.battle_index,
+            gold: self.run.gold,
+            elapsed_time: self.elapsed_time,
+            party,
+            enemies,
         }
     }
 }
@@ -0,0 +1,140 @@
+use crate::model::{HeroState, Team};
+use crate::skill::{DamageType, StatModifier, StatType};
+use crate::status_spec::status_spec;
+use crate::step_api::ActiveRun;
+use crate::trait_spec::{trait_by_id, TraitSpec};
+
+impl ActiveRun {
+    pub(crate) fn base_stat(&self, unit_idx: usize, stat: StatType) -> f32 {
+        let Some(unit) = self.state_ref().and_then(|s| s.units.get(unit_idx)) else {
+            return 0.0;
+        };
+        match stat {
+            StatType::Attack => unit.atk as f32,
+            StatType::Speed => unit.speed,
+            StatType::Evasion => self
+                .enemy_spec_for(unit_idx)
+                .map(|e| e.evasion)
+                .unwrap_or(0.0),
+            _ => stat.shared_base(),
+        }
+    }
+
+    /// Every modifier currently affecting the unit: status modifiers scaled by stacks,
+    /// then passive modifiers of unit traits and, for heroes, run traits.
+    fn stat_modifiers_for(&self, unit_idx: usize) -> Vec<StatModifier> {
+        let mut modifiers = Vec::new();
+        if let Some(row) = self.statuses_ref(unit_idx) {
+            for status in row.iter().filter(|s| s.duration > 0.0) {
+                let stacks = status.stacks.max(1) as f32;
+                modifiers.extend(
+                    status_spec(status.status_type)
+                        .stat_modifiers
+                        .iter()
+                        .map(|m| StatModifier {
+                            stat: m.stat,
+                            flat: m.flat * stacks,
+                            percent: m.percent * stacks,
+                        }),
+                );
+            }
+        }
+
+        for (spec, level) in self.unit_trait_specs(unit_idx) {
+            let factor = spec.level_factor(level);
+            modifiers.extend(spec.stat_modifiers.iter().map(|m| m.scaled(factor)));
+        }
+        modifiers
+    }
+
+    /// Traits whose passive modifiers apply to the unit, with their levels: its own
+    /// (level 1) and, for heroes, run traits.
+    pub(crate) fn unit_trait_specs(&self, unit_idx: usize) -> Vec<(&'static TraitSpec, u32)> {
+        let is_hero = self
+            .state_ref()
+            .and_then(|s| s.units.get(unit_idx))
+            .is_some_and(|u| u.team == Team::Player);
+        let run_traits = if is_hero {
+            self.active_traits.as_slice()
+        } else {
+            &[]
+        };
+        let unit_traits = self
+            .runtime_ref(unit_idx)
+            .map(|r| r.traits.as_slice())
+            .unwrap_or(&[]);
+        run_traits
+            .iter()
+            .map(|owned| (owned.id, owned.level))
+            .chain(unit_traits.iter().map(|id| (*id, 1)))
+            .filter_map(|(id, level)| trait_by_id(id).map(|spec| (spec, level)))
+            .collect()
+    }
+
+    /// Base stat after modifiers: `(base + flat) * (1 + percent)`, never below zero.
+    pub(crate) fn effective_stat(&self, unit_idx: usize, stat: StatType) -> f32 {
+        apply_modifiers(
+            self.base_stat(unit_idx, stat),
+            stat,
+            &self.stat_modifiers_for(unit_idx),
+        )
+    }
+
+    /// Chance for a hit from `src_idx` to land on `dst_idx`: accuracy minus evasion.
+    pub(crate) fn hit_chance(&self, src_idx: usize, dst_idx: usize) -> f32 {
+        let accuracy = self.effective_stat(src_idx, StatType::Accuracy);
+        let evasion = self.effective_stat(dst_idx, StatType::Evasion);
+        (accuracy - evasion).clamp(0.0, 1.0)
+    }
+
+    /// Damage multiplier from the target's armor (physical only) and resistance to
+    /// `damage_type`. Heroes have no defenses; pure damage ignores them.
+    pub(crate) fn mitigation_multiplier(&self, unit_idx: usize, damage_type: DamageType) -> f32 {
+        let Some(spec) = self.enemy_spec_for(unit_idx) else {
+            return 1.0;
+        };
+        if damage_type == DamageType::Pure {
+            return 1.0;
+        }
+        let resistance: f32 = spec
+            .resistances
+            .iter()
+            .filter(|r| r.damage_type == damage_type)
+            .map(|r| r.value)
+            .sum();
+        let mut mult = 1.0 - resistance.clamp(-1.0, 0.9);
+        if damage_type == DamageType::Physical {
+            mult *= 100.0 / (100.0 + spec.armor.max(0.0));
+        }
+        mult
+    }
+
+    /// Hero stat between battles, where only run-trait modifiers apply.
+    pub(crate) fn hero_stat(&self, hero: &HeroState, stat: StatType) -> f32 {
+        let base = match stat {
+            StatType::Attack => hero.atk as f32,
+            StatType::Speed => hero.speed,
+            _ => stat.shared_base(),
+        };
+        let modifiers: Vec<StatModifier> = self
+            .active_traits
+            .iter()
+            .filter_map(|owned| trait_by_id(owned.id).map(|spec| (spec, owned.level)))
+            .flat_map(|(spec, level)| {
+                let factor = spec.level_factor(level);
+                spec.stat_modifiers.iter().map(move |m| m.scaled(factor))
+            })
+            .collect();
+        apply_modifiers(base, stat, &modifiers)
+    }
+}
+
+fn apply_modifiers(base: f32, stat: StatType, modifiers: &[StatModifier]) -> f32 {
+    let (flat, percent) = modifiers
+        .iter()
+        .filter(|m| m.stat == stat)
+        .fold((0.0, 0.0), |(flat, percent), m| {
+            (flat + m.flat, percent + m.percent)
+        });
+    ((base + flat) * (1.0 + percent)).max(0.0)
+}
@@ -1,11 +1,19 @@
+use crate::battle::{player_hp_after_battle, store_party_hp};
+use crate::engine::damage_system::DamageHit;
+use crate::engine::node_system::gain_gold;
 use crate::event::Event;
 use crate::log::push_event;
-use crate::model::Team;
-use crate::skill::StatusType;
-use crate::step_api::{hp2, ActiveRun, TriggerContext, STATUS_TICK_RATE, STATUS_TICK_THRESHOLD};
+use crate::model::{NodeType, Team};
+use crate::node_spec::{BATTLE_GOLD_REWARD, BOSS_GOLD_REWARD};
+use crate::skill::{DamageType, StatusFilter, StatusType};
+use crate::status_spec::{status_spec, DurationPolicy, StackingPolicy, StatusSpec};
+use crate::step_api::{
+    hp2, ActiveRun, ActiveStatus, Immunity, TriggerContext, STATUS_TICK_RATE, STATUS_TICK_THRESHOLD,
+};
 use crate::trait_spec::TriggerType;
 
 impl ActiveRun {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn apply_status(
         &mut self,
         src_idx: usize,
@@ -22,49 +30,70 @@ impl ActiveRun {
         let dst_label = self.actor_label_for_idx(dst_idx);
 
         let mut chance = base_chance;
-        chance += self.runtime_ref(src_idx).map(|r| r.proc_bonus).unwrap_or(0.0);
-        chance -= self.runtime_ref(dst_idx).map(|r| r.res_bonus).unwrap_or(0.0);
+        chance += self
+            .runtime_ref(src_idx)
+            .map(|r| r.proc_bonus)
+            .unwrap_or(0.0);
+        chance -= self
+            .runtime_ref(dst_idx)
+            .map(|r| r.res_bonus)
+            .unwrap_or(0.0);
 
         if !self.roll_success(chance) {
             return;
         }
+        if self.is_immune(dst_idx, status_type) {
+            push_event(
+                events,
+                Event::StatusBlocked {
+                    src: src_label,
+                    src_id: self.unit_id_for_idx(src_idx),
+                    dst: dst_label,
+                    dst_id: self.unit_id_for_idx(dst_idx),
+                    status: status_type.as_str(),
+                },
+            );
+            return;
+        }
 
         let power_mul = self.status_power_mul_for(src_idx, status_type);
 
         let adjusted_power = power * power_mul;
 
+        let spec = status_spec(status_type);
+        let added = stacks.max(1).min(spec.max_stacks.max(1));
+        let incoming = ActiveStatus {
+            status_type,
+            src_idx,
+            stacks: added,
+            duration: duration.max(0.1),
+            base_duration: duration.max(0.1),
+            power: adjusted_power,
+            tick_meter: 0.0,
+        };
         if let Some(row) = self.statuses_mut(dst_idx) {
-            if let Some(existing) = row.iter_mut().find(|s| s.status_type == status_type) {
-                existing.stacks = existing.stacks.saturating_add(stacks.max(1));
-                existing.duration = existing.duration.max(duration);
-                existing.power = existing.power.max(adjusted_power);
-            } else {
-                row.push(crate::step_api::ActiveStatus {
-                    status_type,
-                    stacks: stacks.max(1),
-                    duration: duration.max(0.1),
-                    power: adjusted_power,
-                    tick_meter: 0.0,
-                });
-            }
+            merge_status(row, spec, incoming);
         }
+        let total_stacks = self.status_stacks(dst_idx, status_type);
 
         push_event(
             events,
             Event::StatusApplied {
                 src: src_label,
+                src_id: self.unit_id_for_idx(src_idx),
                 dst: dst_label,
+                dst_id: self.unit_id_for_idx(dst_idx),
                 status: status_type.as_str(),
-                stacks: stacks.max(1),
+                stacks: total_stacks,
+                added,
                 duration: duration.max(0.0).round() as u32,
             },
         );
 
         let context = TriggerContext {
-            trigger_type: TriggerType::OnStatusApplied,
-            src_idx: Some(src_idx),
-            dst_idx: Some(dst_idx),
             applied_status: Some(status_type),
+            stacks: Some(total_stacks),
+            ..TriggerContext::new(TriggerType::OnStatusApplied, Some(src_idx), Some(dst_idx))
         };
         self.process_trait_triggers(context, trait_depth + 1, events);
 
@@ -72,66 +101,31 @@ impl ActiveRun {
         let post_power = power * post_mul;
         if post_power > adjusted_power {
             if let Some(row) = self.statuses_mut(dst_idx) {
-                if let Some(existing) = row.iter_mut().find(|s| s.status_type == status_type) {
+                // The newest instance sits last, which matters for independent statuses.
+                if let Some(existing) = row.iter_mut().rev().find(|s| s.status_type == status_type)
+                {
                     existing.power = existing.power.max(post_power);
                 }
             }
         }
     }
 
-    pub(crate) fn apply_damage(
-        &mut self,
-        src_idx: usize,
-        dst_idx: usize,
-        amount: f32,
-        trait_depth: u8,
-        events: &mut Vec<String>,
-    ) {
-        let src_label = self.actor_label_for_idx(src_idx);
-        let dst_label = self.actor_label_for_idx(dst_idx);
-
-        let mut dst_hp_after = 0.0;
-        if let Some(state) = self.state_mut() {
-            let unit = &mut state.units[dst_idx];
-            let dealt = amount.max(0.01);
-            unit.hp = hp2((unit.hp - dealt).max(0.0));
-            dst_hp_after = unit.hp;
-        }
-
-        push_event(
-            events,
-            Event::DamageDealt {
-                src: src_label,
-                dst: dst_label,
-                amount: amount.max(0.01),
-                dst_hp_after,
-            },
-        );
-
-        let context = TriggerContext {
-            trigger_type: TriggerType::OnDamageDealt,
-            src_idx: Some(src_idx),
-            dst_idx: Some(dst_idx),
-            applied_status: None,
-        };
-        self.process_trait_triggers(context, trait_depth + 1, events);
-    }
-
     pub(crate) fn check_and_emit_battle_end(
         &mut self,
         events: &mut Vec<String>,
     ) -> Option<&'static str> {
         let state = self.state_ref()?;
-        let enemy_alive = state.units.iter().any(|u| u.team == Team::Enemy && u.is_alive());
-        let player_alive = state.units.iter().any(|u| u.team == Team::Player && u.is_alive());
+        let enemy_alive = state
+            .units
+            .iter()
+            .any(|u| u.team == Team::Enemy && u.is_alive());
+        let player_alive = state
+            .units
+            .iter()
+            .any(|u| u.team == Team::Player && u.is_alive());
 
         if !enemy_alive {
-            let player_hp_after = state
-                .units
-                .iter()
-                .find(|u| u.team == Team::Player)
-                .map(|u| u.hp)
-                .unwrap_or(0.0);
+            let player_hp_after = player_hp_after_battle(state);
             push_event(
                 events,
                 Event::BattleEnd {
@@ -139,7 +133,7 @@ impl ActiveRun {
                     player_hp_after,
                 },
             );
-            self.emit_battle_end_triggers("win", events);
+            self.emit_battle_end_triggers(events);
             return Some("win");
         }
 
@@ -151,121 +145,350 @@ impl ActiveRun {
                     player_hp_after: 0.0,
                 },
             );
-            self.emit_battle_end_triggers("lose", events);
+            self.emit_battle_end_triggers(events);
             return Some("lose");
         }
 
         None
     }
 
-    pub(crate) fn tick_statuses(&mut self, dt: f32, events: &mut Vec<String>) -> Option<&'static str> {
+    pub(crate) fn tick_statuses(
+        &mut self,
+        dt: f32,
+        events: &mut Vec<String>,
+    ) -> Option<&'static str> {
         if dt <= 0.0 {
             return None;
         }
 
-        let mut pending_ticks: Vec<(usize, StatusType, f32)> = Vec::new();
-        let mut pending_expire: Vec<(usize, StatusType)> = Vec::new();
+        // (dst, src, status, amount)
+        let mut pending_ticks: Vec<(usize, usize, StatusType, f32)> = Vec::new();
+        // (dst, src, status)
+        let mut pending_expire: Vec<(usize, usize, StatusType)> = Vec::new();
+        let mut pending_decay: Vec<(usize, StatusType, u32)> = Vec::new();
 
+        self.decay_barriers(dt);
         let unit_count = self.unit_count();
         for unit_idx in 0..unit_count {
+            if let Some(runtime) = self.runtime_mut(unit_idx) {
+                runtime.immunities.retain_mut(|immunity| {
+                    immunity.remaining -= dt;
+                    immunity.remaining > 0.0
+                });
+            }
             if let Some(row) = self.statuses_mut(unit_idx) {
                 for status in row.iter_mut() {
                     status.duration -= dt;
                     status.tick_meter += dt * STATUS_TICK_RATE;
 
-                    let tick_amount = match status.status_type {
-                        StatusType::Burn | StatusType::Shock | StatusType::Bleed => {
-                            (status.power * status.stacks as f32).max(0.01)
-                        }
-                        _ => 0.0,
-                    };
+                    let tick_amount =
+                        status_spec(status.status_type).tick_amount(status.power, status.stacks);
 
                     while tick_amount > 0.0 && status.tick_meter >= STATUS_TICK_THRESHOLD {
-                        pending_ticks.push((unit_idx, status.status_type, tick_amount));
+                        pending_ticks.push((
+                            unit_idx,
+                            status.src_idx,
+                            status.status_type,
+                            tick_amount,
+                        ));
                         status.tick_meter -= STATUS_TICK_THRESHOLD;
                     }
 
                     if status.duration <= 0.0 {
-                        pending_expire.push((unit_idx, status.status_type));
+                        if status_spec(status.status_type).decays && status.stacks > 1 {
+                            status.stacks -= 1;
+                            status.duration += status.base_duration;
+                            pending_decay.push((unit_idx, status.status_type, status.stacks));
+                        } else {
+                            pending_expire.push((unit_idx, status.src_idx, status.status_type));
+                        }
                     }
                 }
             }
         }
 
-        for (unit_idx, status_type, amount) in pending_ticks {
-            if let Some(state) = self.state_mut() {
-                if state.units[unit_idx].is_alive() {
-                    state.units[unit_idx].hp = hp2((state.units[unit_idx].hp - amount).max(0.0));
-                }
+        for (unit_idx, src_idx, status_type, amount) in pending_ticks {
+            if status_spec(status_type).heals {
+                self.heal_unit(src_idx, unit_idx, amount, status_type.as_str(), 0, events);
+                continue;
             }
+            let alive = self
+                .state_ref()
+                .is_some_and(|s| s.units[unit_idx].is_alive());
+            if !alive {
+                continue;
+            }
+            self.apply_status_tick(src_idx, unit_idx, status_type, amount, events);
+        }
 
-            let dst = self.actor_label_for_idx(unit_idx);
-            let dst_hp_after = self.state_ref().map(|s| s.units[unit_idx].hp).unwrap_or(0.0);
-
+        for (unit_idx, status_type, stacks) in pending_decay {
             push_event(
                 events,
-                Event::StatusTick {
-                    dst,
+                Event::StatusDecayed {
+                    dst: self.actor_label_for_idx(unit_idx),
+                    dst_id: self.unit_id_for_idx(unit_idx),
                     status: status_type.as_str(),
-                    amount,
-                    dst_hp_after,
+                    stacks,
                 },
             );
-
-            let context = TriggerContext {
-                trigger_type: TriggerType::OnStatusTick,
-                src_idx: None,
-                dst_idx: Some(unit_idx),
-                applied_status: Some(status_type),
-            };
-            self.process_trait_triggers(context, 0, events);
         }
 
-        for (unit_idx, status_type) in pending_expire.iter().copied() {
+        for (unit_idx, _, status_type) in pending_expire.iter().copied() {
             if let Some(row) = self.statuses_mut(unit_idx) {
                 row.retain(|s| !(s.status_type == status_type && s.duration <= 0.0));
             }
         }
 
-        for (unit_idx, status_type) in pending_expire {
+        for (unit_idx, src_idx, status_type) in pending_expire {
             let dst = self.actor_label_for_idx(unit_idx);
             push_event(
                 events,
                 Event::StatusExpired {
                     dst,
+                    dst_id: self.unit_id_for_idx(unit_idx),
                     status: status_type.as_str(),
                 },
             );
+
+            let context = TriggerContext {
+                applied_status: Some(status_type),
+                stacks: Some(self.status_stacks(unit_idx, status_type)),
+                ..TriggerContext::new(TriggerType::OnStatusExpired, Some(src_idx), Some(unit_idx))
+            };
+            self.process_trait_triggers(context, 0, events);
         }
 
         self.check_and_emit_battle_end(events)
     }
 
+    /// Lands one damage tick through the damage pipeline as the status's tick damage type,
+    /// so barrier, mitigation, damage modifiers and lifesteal apply as they do to hits.
+    fn apply_status_tick(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        status_type: StatusType,
+        amount: f32,
+        events: &mut Vec<String>,
+    ) {
+        let spec = status_spec(status_type);
+        let hit = DamageHit::new(src_idx, dst_idx, spec.name, amount, spec.tick_damage_type);
+        let (breakdown, hp_change) = self.land_damage(&hit);
+
+        push_event(
+            events,
+            Event::StatusTick {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                status: status_type.as_str(),
+                damage_type: breakdown.damage_type.as_str(),
+                amount: breakdown.dealt,
+                absorbed: breakdown.absorbed,
+                hp_damage: breakdown.hp_damage,
+                dst_hp_after: hp_change.1,
+            },
+        );
+
+        self.apply_lifesteal(src_idx, dst_idx, breakdown.dealt, 0, events);
+        let context = TriggerContext {
+            applied_status: Some(status_type),
+            amount: Some(breakdown.dealt),
+            damage_type: Some(breakdown.damage_type),
+            stacks: Some(self.status_stacks(dst_idx, status_type)),
+            ..TriggerContext::new(TriggerType::OnStatusTick, Some(src_idx), Some(dst_idx))
+        };
+        self.process_trait_triggers(context, 0, events);
+        self.emit_damage_taken_triggers(context, hp_change, 0, events);
+    }
+
+    /// Product of `StatusSpec::gauge_mult` over active statuses (Freeze slows, Stun halts);
+    /// Haste and other speed buffs go through `effective_stat`.
     pub(crate) fn gauge_speed_multiplier(&self, unit_idx: usize) -> f32 {
         let mut mult = 1.0;
-        if self.has_status(unit_idx, StatusType::Freeze) {
-            mult *= 0.5;
+        let mut seen: Vec<StatusType> = Vec::new();
+        for status in self.statuses_ref(unit_idx).into_iter().flatten() {
+            if status.duration > 0.0 && !seen.contains(&status.status_type) {
+                seen.push(status.status_type);
+                mult *= status_spec(status.status_type).gauge_mult;
+            }
         }
-        if self.has_status(unit_idx, StatusType::Haste) {
-            mult *= 1.25;
+        mult
+    }
+
+    /// A unit that is already due to act while carrying a `skips_turn` status (Stun)
+    /// loses that action and the status is consumed. Returns whether the turn was skipped.
+    pub(crate) fn skip_stunned_turn(&mut self, unit_idx: usize, events: &mut Vec<String>) -> bool {
+        let Some((status_type, src_idx)) = self.statuses_ref(unit_idx).and_then(|row| {
+            row.iter()
+                .find(|s| s.duration > 0.0 && status_spec(s.status_type).skips_turn)
+                .map(|s| (s.status_type, s.src_idx))
+        }) else {
+            return false;
+        };
+        let stacks = self.status_stacks(unit_idx, status_type);
+        if let Some(state) = self.state_mut() {
+            state.units[unit_idx].action_gauge -= 100.0;
         }
-        if self.has_status(unit_idx, StatusType::Stun) {
-            mult = 0.0;
+        if let Some(row) = self.statuses_mut(unit_idx) {
+            row.retain(|s| s.status_type != status_type);
         }
-        mult
+
+        let actor = self.actor_label_for_idx(unit_idx);
+        let actor_id = self.unit_id_for_idx(unit_idx);
+        push_event(
+            events,
+            Event::TurnSkipped {
+                actor,
+                actor_id,
+                reason: status_type.as_str(),
+            },
+        );
+        push_event(
+            events,
+            Event::StatusExpired {
+                dst: actor,
+                dst_id: actor_id,
+                status: status_type.as_str(),
+            },
+        );
+
+        let context = TriggerContext {
+            applied_status: Some(status_type),
+            stacks: Some(stacks),
+            ..TriggerContext::new(TriggerType::OnStatusExpired, Some(src_idx), Some(unit_idx))
+        };
+        self.process_trait_triggers(context, 0, events);
+        true
+    }
+
+    pub(crate) fn is_immune(&self, unit_idx: usize, status_type: StatusType) -> bool {
+        self.runtime_ref(unit_idx)
+            .map(|r| r.immunities.iter().any(|i| i.filter.matches(status_type)))
+            .unwrap_or(false)
+    }
+
+    /// Removes every cleansable status on `dst_idx` matching `filter`.
+    /// Returns how many status instances were removed.
+    pub(crate) fn cleanse_statuses(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        filter: StatusFilter,
+        events: &mut Vec<String>,
+    ) -> u32 {
+        let removable = |s: &ActiveStatus| {
+            filter.matches(s.status_type) && status_spec(s.status_type).cleansable
+        };
+        let removed: Vec<ActiveStatus> = self
+            .statuses_ref(dst_idx)
+            .map(|row| row.iter().filter(|s| removable(s)).cloned().collect())
+            .unwrap_or_default();
+        if let Some(row) = self.statuses_mut(dst_idx) {
+            row.retain(|s| !removable(s));
+        }
+
+        for status in &removed {
+            push_event(
+                events,
+                Event::StatusCleansed {
+                    src: self.actor_label_for_idx(src_idx),
+                    src_id: self.unit_id_for_idx(src_idx),
+                    dst: self.actor_label_for_idx(dst_idx),
+                    dst_id: self.unit_id_for_idx(dst_idx),
+                    status: status.status_type.as_str(),
+                    stacks: status.stacks,
+                },
+            );
+        }
+        removed.len() as u32
+    }
+
+    /// Strips every stack of `status_type` from `dst_idx` and deals `damage_per_stack`
+    /// for each one as a single hit. Returns the stacks consumed.
+    pub(crate) fn consume_status_stacks(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        status_type: StatusType,
+        damage_per_stack: f32,
+        trait_depth: u8,
+        events: &mut Vec<String>,
+    ) -> u32 {
+        let stacks = self.status_stacks(dst_idx, status_type);
+        if stacks == 0 {
+            return 0;
+        }
+        if let Some(row) = self.statuses_mut(dst_idx) {
+            row.retain(|s| s.status_type != status_type);
+        }
+
+        let damage = hp2(damage_per_stack.max(0.0) * stacks as f32);
+        push_event(
+            events,
+            Event::StatusConsumed {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                status: status_type.as_str(),
+                stacks,
+                damage,
+            },
+        );
+        if damage > 0.0 {
+            let source = status_spec(status_type).name;
+            let hit = DamageHit::new(src_idx, dst_idx, source, damage, DamageType::Pure);
+            self.apply_damage(hit, trait_depth, events);
+        }
+        stacks
+    }
+
+    pub(crate) fn grant_immunity(
+        &mut self,
+        src_idx: usize,
+        dst_idx: usize,
+        filter: StatusFilter,
+        duration: f32,
+        events: &mut Vec<String>,
+    ) {
+        let duration = duration.max(0.1);
+        let Some(runtime) = self.runtime_mut(dst_idx) else {
+            return;
+        };
+        match runtime.immunities.iter_mut().find(|i| i.filter == filter) {
+            Some(existing) => existing.remaining = existing.remaining.max(duration),
+            None => runtime.immunities.push(Immunity {
+                filter,
+                remaining: duration,
+            }),
+        }
+
+        push_event(
+            events,
+            Event::ImmunityGranted {
+                src: self.actor_label_for_idx(src_idx),
+                src_id: self.unit_id_for_idx(src_idx),
+                dst: self.actor_label_for_idx(dst_idx),
+                dst_id: self.unit_id_for_idx(dst_idx),
+                filter: filter.as_str(),
+                duration,
+            },
+        );
     }
 
     pub(crate) fn finalize_battle(&mut self, outcome: &'static str, events: &mut Vec<String>) {
         if outcome == "win" {
-            let player_hp = self
-                .state_ref()
-                .and_then(|state| state.units.iter().find(|u| u.team == Team::Player))
-                .map(|u| u.hp)
-                .unwrap_or(self.run.player_hp);
-
-            self.run.player_hp = player_hp;
-            let recover = hp2(self.run.player_max_hp * 0.20);
-            self.run.player_hp = hp2((self.run.player_hp + recover).min(self.run.player_max_hp));
+            if let Some(battle) = self.current_battle.as_ref() {
+                store_party_hp(&mut self.run, &battle.state);
+            }
+            self.run.recover_party(0.20);
+            let gold_reward = match self.current_node_type() {
+                Some(NodeType::Boss) => BOSS_GOLD_REWARD,
+                _ => BATTLE_GOLD_REWARD,
+            };
+            gain_gold(&mut self.run, gold_reward, events);
             self.current_battle = None;
             self.waiting_for_input = false;
 
@@ -279,9 +502,13 @@ impl ActiveRun {
                         final_node_index: self.node_index,
                     },
                 );
+            } else {
+                self.open_trait_offer(events);
             }
         } else {
-            self.run.player_hp = 0.0;
+            for hero in &mut self.run.party {
+                hero.hp = 0.0;
+            }
             self.current_battle = None;
             self.waiting_for_input = false;
             self.ended = true;
@@ -296,3 +523,375 @@ impl ActiveRun {
         }
     }
 }
+
+/// Folds `incoming` into `row` according to the status's stacking and duration policies.
+fn merge_status(row: &mut Vec<ActiveStatus>, spec: &StatusSpec, incoming: ActiveStatus) {
+    let cap = spec.max_stacks.max(1);
+    if spec.stacking == StackingPolicy::Independent {
+        let count = row
+            .iter()
+            .filter(|s| s.status_type == incoming.status_type)
+            .count();
+        if count as u32 >= cap {
+            // At the instance cap the instance closest to expiring makes room.
+            if let Some(pos) = row
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.status_type == incoming.status_type)
+                .min_by(|(_, a), (_, b)| a.duration.total_cmp(&b.duration))
+                .map(|(pos, _)| pos)
+            {
+                row.remove(pos);
+            }
+        }
+        row.push(incoming);
+        return;
+    }
+
+    let Some(existing) = row
+        .iter_mut()
+        .find(|s| s.status_type == incoming.status_type)
+    else {
+        row.push(incoming);
+        return;
+    };
+    existing.stacks = match spec.stacking {
+        StackingPolicy::Add => existing.stacks.saturating_add(incoming.stacks),
+        StackingPolicy::Max => existing.stacks.max(incoming.stacks),
+        StackingPolicy::Refresh | StackingPolicy::Independent => existing.stacks,
+    }
+    .min(cap);
+    existing.duration = match spec.duration {
+        DurationPolicy::Refresh => incoming.duration,
+        DurationPolicy::Extend => {
+            (existing.duration + incoming.duration).min(spec.max_duration.max(incoming.duration))
+        }
+        DurationPolicy::Longest => existing.duration.max(incoming.duration),
+    };
+    existing.base_duration = incoming.base_duration;
+    existing.src_idx = incoming.src_idx;
+    existing.power = existing.power.max(incoming.power);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enemy_spec::ROGUE_DRONE;
+    use crate::engine::damage_system::DamageHit;
+    use crate::skill::{Condition, DamageType, StatusFilter, StatusType};
+    use crate::status_spec::{StatusCategory, BREAK, FREEZE, HASTE};
+    use crate::step_api::tests::run_against;
+    use crate::step_api::ActiveRun;
+    use crate::trait_spec::{TraitRarity, TraitSpec, TriggerRule, TriggerType};
+
+    fn inflict(run: &mut ActiveRun, dst_idx: usize, status_type: StatusType, stacks: u32) {
+        inflict_for(run, dst_idx, status_type, stacks, 5.0);
+    }
+
+    fn inflict_for(
+        run: &mut ActiveRun,
+        dst_idx: usize,
+        status_type: StatusType,
+        stacks: u32,
+        duration: f32,
+    ) -> Vec<String> {
+        let mut events = Vec::new();
+        run.apply_status(
+            0,
+            dst_idx,
+            status_type,
+            1.0,
+            duration,
+            stacks,
+            1.0,
+            0,
+            &mut events,
+        );
+        events
+    }
+
+    fn durations(run: &ActiveRun, name: &str) -> Vec<f32> {
+        run.snapshot().enemies[0]
+            .statuses
+            .iter()
+            .filter(|s| s.status_type == name)
+            .map(|s| s.duration)
+            .collect()
+    }
+
+    #[test]
+    fn break_raises_damage_taken_per_stack() {
+        let mut run = run_against(1, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut events,
+        );
+        let plain = ROGUE_DRONE.max_hp - run.snapshot().enemies[0].hp;
+
+        inflict(&mut run, 1, StatusType::Break, 2);
+        let hp_before = run.snapshot().enemies[0].hp;
+        run.apply_damage(
+            DamageHit::new(0, 1, "test", 10.0, DamageType::Pure),
+            0,
+            &mut events,
+        );
+        let broken = hp_before - run.snapshot().enemies[0].hp;
+
+        assert!((plain - 10.0).abs() < 1e-3);
+        let expected = 10.0 * (1.0 + 2.0 * BREAK.damage_modifiers[0].value);
+        assert!((broken - expected).abs() < 1e-3, "got {broken}");
+    }
+
+    #[test]
+    fn applications_follow_the_status_stacking_policy() {
+        let mut run = run_against(4, &[&ROGUE_DRONE]);
+        inflict(&mut run, 1, StatusType::Break, 3);
+        inflict(&mut run, 1, StatusType::Break, 3);
+        inflict(&mut run, 1, StatusType::Bleed, 1);
+        inflict(&mut run, 1, StatusType::Bleed, 1);
+        inflict(&mut run, 1, StatusType::Freeze, 2);
+
+        let statuses = &run.snapshot().enemies[0].statuses;
+        let rows = |name: &str| -> Vec<u32> {
+            statuses
+                .iter()
+                .filter(|s| s.status_type == name)
+                .map(|s| s.stacks)
+                .collect()
+        };
+        assert_eq!(
+            rows("Break"),
+            [BREAK.max_stacks],
+            "Add stacks up to the cap"
+        );
+        assert_eq!(
+            rows("Bleed"),
+            [1, 1],
+            "Independent keeps separate instances"
+        );
+        assert_eq!(rows("Freeze"), [1], "Freeze never exceeds one stack");
+        assert_eq!(run.status_count(1), 3);
+    }
+
+    #[test]
+    fn status_applied_reports_total_stacks() {
+        let mut run = run_against(5, &[&ROGUE_DRONE]);
+        inflict(&mut run, 1, StatusType::Break, 2);
+        let events = inflict_for(&mut run, 1, StatusType::Break, 2, 5.0);
+        let applied = events
+            .iter()
+            .find(|line| line.contains("\"kind\":\"StatusApplied\""))
+            .expect("Break should apply");
+        assert!(applied.contains("\"stacks\":4"), "{applied}");
+        assert!(applied.contains("\"added\":2"), "{applied}");
+    }
+
+    #[test]
+    fn duration_policies_refresh_extend_and_keep_instances_apart() {
+        let mut run = run_against(6, &[&ROGUE_DRONE]);
+        inflict_for(&mut run, 1, StatusType::Haste, 1, 4.0);
+        inflict_for(&mut run, 1, StatusType::Haste, 1, 4.0);
+        assert_eq!(durations(&run, "Haste"), [8.0], "Haste extends");
+        inflict_for(&mut run, 1, StatusType::Haste, 1, 4.0);
+        assert_eq!(
+            durations(&run, "Haste"),
+            [HASTE.max_duration],
+            "extension is capped"
+        );
+
+        inflict_for(&mut run, 1, StatusType::Might, 1, 6.0);
+        inflict_for(&mut run, 1, StatusType::Might, 1, 2.0);
+        assert_eq!(
+            durations(&run, "Might"),
+            [2.0],
+            "Might restarts at the new duration"
+        );
+
+        inflict_for(&mut run, 1, StatusType::Bleed, 1, 5.0);
+        inflict_for(&mut run, 1, StatusType::Bleed, 1, 3.0);
+        assert_eq!(
+            durations(&run, "Bleed"),
+            [5.0, 3.0],
+            "Bleed instances keep their own"
+        );
+    }
+
+    #[test]
+    fn decaying_status_loses_one_stack_per_expiry() {
+        let mut run = run_against(7, &[&ROGUE_DRONE]);
+        inflict_for(&mut run, 1, StatusType::Burn, 3, 1.0);
+
+        let mut events = Vec::new();
+        run.tick_statuses(1.05, &mut events);
+        assert_eq!(run.status_stacks(1, StatusType::Burn), 2);
+        assert!(events
+            .iter()
+            .any(|line| line.contains("\"kind\":\"StatusDecayed\"")));
+        assert!(!events
+            .iter()
+            .any(|line| line.contains("\"kind\":\"StatusExpired\"")));
+    }
+
+    #[test]
+    fn status_ticks_are_credited_to_the_applier() {
+        let mut run = run_against(8, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        run.apply_status(1, 0, StatusType::Burn, 1.0, 5.0, 1, 1.0, 0, &mut events);
+        run.apply_status(0, 1, StatusType::Burn, 1.0, 5.0, 1, 1.0, 0, &mut events);
+
+        events.clear();
+        run.tick_statuses(1.05, &mut events);
+        let ticks: Vec<&String> = events
+            .iter()
+            .filter(|line| line.contains("\"kind\":\"StatusTick\""))
+            .collect();
+        assert_eq!(ticks.len(), 2);
+        assert!(ticks[0].contains("\"src_id\":1") && ticks[0].contains("\"dst_id\":0"));
+        assert!(ticks[1].contains("\"src_id\":0") && ticks[1].contains("\"dst_id\":1"));
+    }
+
+    #[test]
+    fn cleanse_removes_matching_cleansable_statuses() {
+        let mut run = run_against(9, &[&ROGUE_DRONE]);
+        inflict(&mut run, 1, StatusType::Burn, 2);
+        inflict(&mut run, 1, StatusType::Break, 1);
+        inflict(&mut run, 1, StatusType::Might, 1);
+
+        let mut events = Vec::new();
+        let debuffs = StatusFilter::Category(StatusCategory::Debuff);
+        assert_eq!(run.cleanse_statuses(1, 1, debuffs, &mut events), 1);
+        assert!(!run.has_status(1, StatusType::Burn));
+        assert!(
+            run.has_status(1, StatusType::Break),
+            "Break is not cleansable"
+        );
+        assert!(
+            run.has_status(1, StatusType::Might),
+            "buffs survive a debuff cleanse"
+        );
+        assert!(
+            events[0].contains("\"kind\":\"StatusCleansed\""),
+            "{}",
+            events[0]
+        );
+
+        run.cleanse_statuses(0, 1, StatusFilter::Type(StatusType::Might), &mut events);
+        assert!(!run.has_status(1, StatusType::Might));
+    }
+
+    #[test]
+    fn consuming_stacks_deals_damage_per_stack() {
+        let mut run = run_against(10, &[&ROGUE_DRONE]);
+        inflict(&mut run, 1, StatusType::Burn, 3);
+
+        let mut events = Vec::new();
+        assert_eq!(
+            run.consume_status_stacks(0, 1, StatusType::Burn, 4.0, 0, &mut events),
+            3
+        );
+        assert!(!run.has_status(1, StatusType::Burn));
+        assert!((ROGUE_DRONE.max_hp - run.snapshot().enemies[0].hp - 12.0).abs() < 1e-3);
+        let consumed = events
+            .iter()
+            .find(|line| line.contains("\"kind\":\"StatusConsumed\""))
+            .expect("Burn should be consumed");
+        assert!(consumed.contains("\"stacks\":3"), "{consumed}");
+
+        assert_eq!(
+            run.consume_status_stacks(0, 1, StatusType::Burn, 4.0, 0, &mut events),
+            0
+        );
+    }
+
+    #[test]
+    fn immunity_blocks_matching_statuses_until_it_runs_out() {
+        let mut run = run_against(11, &[&ROGUE_DRONE]);
+        let mut events = Vec::new();
+        let debuffs = StatusFilter::Category(StatusCategory::Debuff);
+        run.grant_immunity(1, 1, debuffs, 1.0, &mut events);
+
+        let events = inflict_for(&mut run, 1, StatusType::Burn, 1, 5.0);
+        assert!(events
+            .iter()
+            .any(|line| line.contains("\"kind\":\"StatusBlocked\"")));
+        assert!(!run.has_status(1, StatusType::Burn));
+        inflict(&mut run, 1, StatusType::Haste, 1);
+        assert!(run.has_status(1, StatusType::Haste), "buffs still land");
+
+        run.tick_statuses(1.05, &mut Vec::new());
+        inflict(&mut run, 1, StatusType::Burn, 1);
+        assert!(run.has_status(1, StatusType::Burn), "immunity expired");
+    }
+
+    #[test]
+    fn freeze_slows_gauge_and_stun_halts_it() {
+        let mut run = run_against(2, &[&ROGUE_DRONE]);
+        assert_eq!(run.gauge_speed_multiplier(1), 1.0);
+
+        inflict(&mut run, 1, StatusType::Freeze, 1);
+        assert_eq!(run.gauge_speed_multiplier(1), FREEZE.gauge_mult);
+
+        inflict(&mut run, 1, StatusType::Stun, 1);
+        assert_eq!(run.gauge_speed_multiplier(1), 0.0);
+    }
+
+    #[test]
+    fn stun_cancels_an_already_queued_action() {
+        let mut run = run_against(3, &[&ROGUE_DRONE]);
+        if let Some(state) = run.state_mut() {
+            state.units[1].action_gauge = 100.0;
+        }
+        inflict(&mut run, 1, StatusType::Stun, 1);
+
+        let result = run.step_once(0.1, None);
+        let by_drone = |kind: &str| {
+            result.events.iter().any(|line| {
+                line.contains(&format!("\"kind\":\"{kind}\"")) && line.contains("\"actor_id\":1")
+            })
+        };
+        assert!(by_drone("TurnSkipped"));
+        assert!(!by_drone("ActionUsed"));
+        assert!(
+            !run.has_status(1, StatusType::Stun),
+            "Stun is consumed by the skipped turn"
+        );
+        assert!(run.snapshot().enemies[0].action_gauge < 100.0);
+    }
+
+    const STUN_WATCH_COND_ALL: [Condition; 2] = [
+        Condition::SrcIsPlayer,
+        Condition::AppliedStatusIs(StatusType::Stun),
+    ];
+    const STUN_WATCH_RULES: [TriggerRule; 1] = [TriggerRule {
+        trigger: TriggerType::OnStatusExpired,
+        condition: Condition::All(&STUN_WATCH_COND_ALL),
+        effects: &[],
+    }];
+    /// No effects, so an expiring Stun shows up as `TraitTriggered` alone.
+    const STUN_WATCH: TraitSpec = TraitSpec {
+        id: "stun_watch",
+        name: "Stun Watch",
+        description: "Reacts when a Stun the party applied expires.",
+        triggers: &STUN_WATCH_RULES,
+        stat_modifiers: &[],
+        damage_modifiers: &[],
+        rarity: TraitRarity::Common,
+        max_level: 1,
+        level_scaling: 0.0,
+    };
+
+    #[test]
+    fn consumed_stun_fires_status_expired_triggers() {
+        let mut run = run_against(4, &[&ROGUE_DRONE]);
+        run.injected_traits = vec![&STUN_WATCH];
+        inflict(&mut run, 1, StatusType::Stun, 1);
+
+        let mut events = Vec::new();
+        assert!(run.skip_stunned_turn(1, &mut events));
+        assert!(events.iter().any(|line| {
+            line.contains("\"kind\":\"TraitTriggered\"")
+                && line.contains("\"trigger_type\":\"OnStatusExpired\"")
+        }));
+    }
+}
@@ -1,8 +1,9 @@
+use crate::engine::damage_system::DamageHit;
 use crate::event::Event;
 use crate::log::push_event;
-use crate::skill::{EffectSpec, StatType, StatusType};
+use crate::skill::{buff_status_for, DamageStage, DamageType, EffectSpec, StatType};
 use crate::step_api::{ActiveRun, TriggerContext, TRAIT_CHAIN_DEPTH_MAX};
-use crate::trait_spec::{trait_by_id, TriggerType};
+use crate::trait_spec::{trait_by_id, TraitSpec, TriggerType};
 
 impl ActiveRun {
     fn push_trait_effect_event(
@@ -35,12 +36,15 @@ impl ActiveRun {
         match effect {
             EffectSpec::DealDamage { multiplier, flat } => {
                 if let (Some(src_idx), Some(dst_idx)) = (context.src_idx, context.dst_idx) {
-                    let atk = self
-                        .state_ref()
-                        .map(|s| s.units[src_idx].atk as f32)
-                        .unwrap_or(1.0);
-                    let amount = (atk * multiplier + flat).max(0.01);
-                    self.apply_damage(src_idx, dst_idx, amount, depth, events);
+                    let base = self.effective_stat(src_idx, StatType::Attack) * multiplier;
+                    // Follow-up hits share the type of the hit or skill that triggered them.
+                    let damage_type = context
+                        .damage_type
+                        .or(context.skill.map(|s| DamageType::from_tags(s.tags)))
+                        .unwrap_or(DamageType::Physical);
+                    let hit = DamageHit::new(src_idx, dst_idx, trait_name, base, damage_type)
+                        .with_bonus(DamageStage::Additive, trait_name, flat);
+                    self.apply_damage(hit, depth, events);
                     self.push_trait_effect_event(
                         trait_name,
                         format!("DealDamage x{multiplier:.2} +{flat}"),
@@ -74,14 +78,8 @@ impl ActiveRun {
                     );
                 }
             }
-            EffectSpec::ConditionalDamageAmp { condition, amp } => {
-                if self.evaluate_condition(condition, context) {
-                    let next = EffectSpec::DealDamage {
-                        multiplier: amp,
-                        flat: 0.0,
-                    };
-                    self.process_trait_effect(trait_name, next, context, depth + 1, events);
-                }
+            EffectSpec::ConditionalDamageAmp { .. } => {
+                // Applied inside the hit by `registered_damage_modifiers`; the hit has landed.
             }
             EffectSpec::ConditionalApplyStatus {
                 condition,
@@ -107,14 +105,11 @@ impl ActiveRun {
                 amount,
                 duration,
             } => {
-                if let Some(src_idx) = context.src_idx {
-                    let status_type = match stat {
-                        StatType::Attack => StatusType::Might,
-                        StatType::Speed => StatusType::Haste,
-                    };
+                let status_type = buff_status_for(stat);
+                if let (Some(owner_idx), Some(status_type)) = (context.self_idx(), status_type) {
                     self.apply_status(
-                        src_idx,
-                        src_idx,
+                        owner_idx,
+                        owner_idx,
                         status_type,
                         1.0,
                         duration,
@@ -131,20 +126,28 @@ impl ActiveRun {
                 }
             }
             EffectSpec::AddProcBonus { amount } => {
-                if let Some(src_idx) = context.src_idx {
-                    self.add_proc_bonus(src_idx, amount);
+                if let Some(owner_idx) = context.self_idx() {
+                    self.add_proc_bonus(owner_idx, amount);
                 }
-                self.push_trait_effect_event(trait_name, format!("AddProcBonus +{amount:.2}"), events);
+                self.push_trait_effect_event(
+                    trait_name,
+                    format!("AddProcBonus +{amount:.2}"),
+                    events,
+                );
             }
             EffectSpec::AddResBonus { amount } => {
-                if let Some(src_idx) = context.src_idx {
-                    self.add_res_bonus(src_idx, amount);
+                if let Some(owner_idx) = context.self_idx() {
+                    self.add_res_bonus(owner_idx, amount);
                 }
-                self.push_trait_effect_event(trait_name, format!("AddResBonus +{amount:.2}"), events);
+                self.push_trait_effect_event(
+                    trait_name,
+                    format!("AddResBonus +{amount:.2}"),
+                    events,
+                );
             }
             EffectSpec::ModifyStatusPower { status_type, mul } => {
-                if let Some(src_idx) = context.src_idx {
-                    self.update_status_power_mul(src_idx, status_type, mul);
+                if let Some(owner_idx) = context.self_idx() {
+                    self.update_status_power_mul(owner_idx, status_type, mul);
                 }
                 self.push_trait_effect_event(
                     trait_name,
@@ -180,7 +183,10 @@ impl ActiveRun {
             EffectSpec::DealPureDamage { target, amount } => {
                 if let Some(dst_idx) = self.resolve_effect_target(target, context) {
                     let src_idx = context.src_idx.unwrap_or(dst_idx);
-                    self.apply_damage(src_idx, dst_idx, amount.max(0.01), depth, events);
+                    let amount = amount.max(0.01);
+                    let damage_type = DamageType::Pure;
+                    let hit = DamageHit::new(src_idx, dst_idx, trait_name, amount, damage_type);
+                    self.apply_damage(hit, depth, events);
                     self.push_trait_effect_event(
                         trait_name,
                         format!("DealPureDamage {:.2}", amount.max(0.01)),
@@ -188,7 +194,119 @@ impl ActiveRun {
                     );
                 }
             }
+            EffectSpec::Cleanse { target, filter } => {
+                if let Some(dst_idx) = self.resolve_effect_target(target, context) {
+                    let src_idx = context.src_idx.unwrap_or(dst_idx);
+                    let removed = self.cleanse_statuses(src_idx, dst_idx, filter, events);
+                    self.push_trait_effect_event(
+                        trait_name,
+                        format!("Cleanse {} x{removed}", filter.as_str()),
+                        events,
+                    );
+                }
+            }
+            EffectSpec::ConsumeStacks {
+                target,
+                status_type,
+                damage_per_stack,
+            } => {
+                if let Some(dst_idx) = self.resolve_effect_target(target, context) {
+                    let src_idx = context.src_idx.unwrap_or(dst_idx);
+                    let stacks = self.consume_status_stacks(
+                        src_idx,
+                        dst_idx,
+                        status_type,
+                        damage_per_stack,
+                        depth,
+                        events,
+                    );
+                    self.push_trait_effect_event(
+                        trait_name,
+                        format!("ConsumeStacks {} x{stacks}", status_type.as_str()),
+                        events,
+                    );
+                }
+            }
+            EffectSpec::GrantImmunity {
+                target,
+                filter,
+                duration,
+            } => {
+                if let Some(dst_idx) = self.resolve_effect_target(target, context) {
+                    let src_idx = context.src_idx.unwrap_or(dst_idx);
+                    self.grant_immunity(src_idx, dst_idx, filter, duration, events);
+                    self.push_trait_effect_event(
+                        trait_name,
+                        format!("GrantImmunity {} {duration:.1}s", filter.as_str()),
+                        events,
+                    );
+                }
+            }
+            EffectSpec::Heal { target, amount } => {
+                if let Some(dst_idx) = self.resolve_effect_target(target, context) {
+                    let src_idx = context.src_idx.unwrap_or(dst_idx);
+                    let damage = context.amount.unwrap_or(0.0);
+                    let heal = self.heal_amount_for(amount, dst_idx, damage);
+                    let healed = self.heal_unit(src_idx, dst_idx, heal, trait_name, depth, events);
+                    self.push_trait_effect_event(trait_name, format!("Heal +{healed:.2}"), events);
+                }
+            }
+            EffectSpec::GrantBarrier {
+                target,
+                amount,
+                decay,
+            } => {
+                if let Some(dst_idx) = self.resolve_effect_target(target, context) {
+                    let src_idx = context.src_idx.unwrap_or(dst_idx);
+                    self.grant_barrier(src_idx, dst_idx, amount, decay, events);
+                    self.push_trait_effect_event(
+                        trait_name,
+                        format!("GrantBarrier +{amount:.2}"),
+                        events,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Run-level traits are owned by the hero involved in the trigger;
+    /// bestiary traits (always level 1) by their living enemy.
+    fn trait_owners(
+        &self,
+        context: TriggerContext,
+    ) -> Vec<(Option<usize>, &'static TraitSpec, u32)> {
+        let player_idx = self.party_unit_for(context);
+        let mut owners: Vec<(Option<usize>, &'static TraitSpec, u32)> = self
+            .active_traits
+            .iter()
+            .filter_map(|owned| trait_by_id(owned.id).map(|spec| (player_idx, spec, owned.level)))
+            .collect();
+        #[cfg(test)]
+        owners.extend(
+            self.injected_traits
+                .iter()
+                .map(|&spec| (player_idx, spec, 1)),
+        );
+
+        for unit_idx in 0..self.unit_count() {
+            let alive = self
+                .state_ref()
+                .map(|s| s.units[unit_idx].is_alive())
+                .unwrap_or(false);
+            if !alive {
+                continue;
+            }
+            if let Some(runtime) = self.runtime_ref(unit_idx) {
+                owners.extend(
+                    runtime
+                        .traits
+                        .iter()
+                        .filter_map(|id| trait_by_id(id))
+                        .map(|spec| (Some(unit_idx), spec, 1)),
+                );
+            }
         }
+        owners
     }
 
     pub(crate) fn process_trait_triggers(
@@ -201,10 +319,12 @@ impl ActiveRun {
             return;
         }
 
-        let trait_ids = self.active_traits.clone();
-        for trait_id in trait_ids {
-            let Some(spec) = trait_by_id(trait_id) else {
-                continue;
+        for (owner_idx, spec, level) in self.trait_owners(context) {
+            let factor = spec.level_factor(level);
+            let context = TriggerContext {
+                owner_idx,
+                skill: context.skill.or(self.resolving_skill),
+                ..context
             };
 
             for rule in spec.triggers {
@@ -224,24 +344,15 @@ impl ActiveRun {
                 );
 
                 for effect in rule.effects {
-                    self.process_trait_effect(spec.name, *effect, context, depth + 1, events);
+                    let effect = effect.scaled(factor);
+                    self.process_trait_effect(spec.name, effect, context, depth + 1, events);
                 }
             }
         }
     }
 
-    pub(crate) fn emit_battle_end_triggers(
-        &mut self,
-        result: &'static str,
-        events: &mut Vec<String>,
-    ) {
-        let _ = result;
-        let context = TriggerContext {
-            trigger_type: TriggerType::OnBattleEnd,
-            src_idx: None,
-            dst_idx: None,
-            applied_status: None,
-        };
+    pub(crate) fn emit_battle_end_triggers(&mut self, events: &mut Vec<String>) {
+        let context = TriggerContext::new(TriggerType::OnBattleEnd, None, None);
         self.process_trait_triggers(context, 0, events);
     }
 }
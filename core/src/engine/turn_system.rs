@@ -1,15 +1,32 @@
+use crate::engine::damage_system::DamageHit;
 use crate::event::Event;
 use crate::log::{push_event, set_log_tick};
 use crate::model::Team;
-use crate::skill::{player_skill_for_slot, skill_by_id, EffectSpec, SkillSpec, StatType, StatusType, BASIC_ATTACK};
+use crate::skill::{
+    buff_status_for, skill_for_slot, DamageStage, DamageType, EffectSpec, SkillSpec,
+    SkillTargeting, StatType, BASIC_ATTACK,
+};
 use crate::step_api::{ActionKind, ActiveRun, StepResult, TriggerContext};
 use crate::trait_spec::TriggerType;
 
 impl ActiveRun {
-    fn choose_skill_for_action(&self, action: ActionKind) -> &'static SkillSpec {
+    pub(crate) fn choose_skill_for_action(
+        &self,
+        actor_idx: usize,
+        action: ActionKind,
+    ) -> &'static SkillSpec {
         match action {
-            ActionKind::BasicAttack => &BASIC_ATTACK,
-            ActionKind::SkillSlot(slot) => player_skill_for_slot(slot),
+            ActionKind::SkillSlot(slot) => {
+                let skills = self
+                    .hero_for_unit(actor_idx)
+                    .map(|h| h.skills)
+                    .unwrap_or(&[]);
+                skill_for_slot(skills, slot)
+            }
+            ActionKind::BasicAttack
+            | ActionKind::NodeChoice(_)
+            | ActionKind::PathChoice(_)
+            | ActionKind::TraitChoice(_) => &BASIC_ATTACK,
         }
     }
 
@@ -21,37 +38,70 @@ impl ActiveRun {
         events: &mut Vec<String>,
     ) {
         let actor = self.actor_label_for_idx(actor_idx);
+        let actor_id = self.unit_id_for_idx(actor_idx);
+
+        push_event(events, Event::TurnReady { actor, actor_id });
+        self.resolving_skill = Some(skill);
+        if let Some(runtime) = self.runtime_mut(actor_idx) {
+            runtime.turns_taken += 1;
+        }
+        let context_turn =
+            TriggerContext::new(TriggerType::OnTurnStart, Some(actor_idx), Some(actor_idx));
+        self.process_trait_triggers(context_turn, 0, events);
 
-        push_event(events, Event::TurnReady { actor });
         push_event(
             events,
             Event::ActionUsed {
                 actor,
+                actor_id,
                 action_name: skill.name,
             },
         );
 
-        let context_action = TriggerContext {
-            trigger_type: TriggerType::OnActionUsed,
-            src_idx: Some(actor_idx),
-            dst_idx: Some(target_idx),
-            applied_status: None,
-        };
+        let context_action =
+            TriggerContext::new(TriggerType::OnActionUsed, Some(actor_idx), Some(target_idx));
         self.process_trait_triggers(context_action, 0, events);
 
-        let mut damage_amp = 1.0_f32;
+        // Skill amps resolved so far; each applies to the hits that follow it.
+        let mut amps: Vec<f32> = Vec::new();
+        let mut damage_dealt = 0.0_f32;
+        let damage_type = DamageType::from_tags(skill.tags);
+
+        // One accuracy and one crit roll per use, shared by every hit of the skill.
+        let attacks = skill
+            .effects
+            .iter()
+            .any(|e| matches!(e, EffectSpec::DealDamage { .. }));
+        let hostile = self
+            .state_ref()
+            .is_some_and(|s| s.units[actor_idx].team != s.units[target_idx].team);
+        let missed =
+            attacks && hostile && !self.roll_success(self.hit_chance(actor_idx, target_idx));
+        let crit = attacks
+            && !missed
+            && self.roll_success(self.effective_stat(actor_idx, StatType::CritChance));
+        if missed {
+            self.emit_miss(actor_idx, target_idx, damage_type, events);
+        }
 
         for effect in skill.effects {
+            // A miss voids everything the skill would have done to its target.
+            if missed && self.effect_landing_idx(*effect, context_action) == Some(target_idx) {
+                continue;
+            }
             match *effect {
                 EffectSpec::DealDamage { multiplier, flat } => {
-                    let atk = self
-                        .state_ref()
-                        .map(|s| s.units[actor_idx].atk as f32)
-                        .unwrap_or(1.0);
-                    let base = atk * skill.base_damage_multiplier * multiplier * damage_amp;
+                    let atk = self.effective_stat(actor_idx, StatType::Attack);
+                    let base = atk * skill.base_damage_multiplier * multiplier;
                     let bonus = skill.flat_bonus_damage.unwrap_or(0.0) + flat;
-                    let amount = (base + bonus).max(0.01);
-                    self.apply_damage(actor_idx, target_idx, amount, 0, events);
+                    let mut hit =
+                        DamageHit::new(actor_idx, target_idx, skill.name, base, damage_type)
+                            .with_crit(crit)
+                            .with_bonus(DamageStage::Additive, skill.name, bonus);
+                    for amp in &amps {
+                        hit = hit.with_bonus(DamageStage::Multiplicative, skill.name, amp - 1.0);
+                    }
+                    damage_dealt += self.apply_damage(hit, 0, events);
                 }
                 EffectSpec::ApplyStatus {
                     status_type,
@@ -73,14 +123,8 @@ impl ActiveRun {
                     );
                 }
                 EffectSpec::ConditionalDamageAmp { condition, amp } => {
-                    let context = TriggerContext {
-                        trigger_type: TriggerType::OnActionUsed,
-                        src_idx: Some(actor_idx),
-                        dst_idx: Some(target_idx),
-                        applied_status: None,
-                    };
-                    if self.evaluate_condition(condition, context) {
-                        damage_amp *= amp.max(0.1);
+                    if self.evaluate_condition(condition, context_action) {
+                        amps.push(amp.max(0.1));
                     }
                 }
                 EffectSpec::ConditionalApplyStatus {
@@ -91,13 +135,7 @@ impl ActiveRun {
                     stacks,
                     power,
                 } => {
-                    let context = TriggerContext {
-                        trigger_type: TriggerType::OnActionUsed,
-                        src_idx: Some(actor_idx),
-                        dst_idx: Some(target_idx),
-                        applied_status: None,
-                    };
-                    if self.evaluate_condition(condition, context) {
+                    if self.evaluate_condition(condition, context_action) {
                         self.apply_status(
                             actor_idx,
                             target_idx,
@@ -116,9 +154,8 @@ impl ActiveRun {
                     amount,
                     duration,
                 } => {
-                    let status_type = match stat {
-                        StatType::Attack => StatusType::Might,
-                        StatType::Speed => StatusType::Haste,
+                    let Some(status_type) = buff_status_for(stat) else {
+                        continue;
                     };
                     self.apply_status(
                         actor_idx,
@@ -146,15 +183,7 @@ impl ActiveRun {
                     status_type,
                     stacks,
                 } => {
-                    if let Some(dst_idx) = self.resolve_effect_target(
-                        target,
-                        TriggerContext {
-                            trigger_type: TriggerType::OnActionUsed,
-                            src_idx: Some(actor_idx),
-                            dst_idx: Some(target_idx),
-                            applied_status: None,
-                        },
-                    ) {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
                         self.apply_status(
                             actor_idx,
                             dst_idx,
@@ -169,62 +198,254 @@ impl ActiveRun {
                     }
                 }
                 EffectSpec::DealPureDamage { target, amount } => {
-                    if let Some(dst_idx) = self.resolve_effect_target(
-                        target,
-                        TriggerContext {
-                            trigger_type: TriggerType::OnActionUsed,
-                            src_idx: Some(actor_idx),
-                            dst_idx: Some(target_idx),
-                            applied_status: None,
-                        },
-                    ) {
-                        self.apply_damage(actor_idx, dst_idx, amount.max(0.01), 0, events);
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        let hit = DamageHit::new(
+                            actor_idx,
+                            dst_idx,
+                            skill.name,
+                            amount,
+                            DamageType::Pure,
+                        );
+                        damage_dealt += self.apply_damage(hit, 0, events);
+                    }
+                }
+                EffectSpec::Cleanse { target, filter } => {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        self.cleanse_statuses(actor_idx, dst_idx, filter, events);
+                    }
+                }
+                EffectSpec::ConsumeStacks {
+                    target,
+                    status_type,
+                    damage_per_stack,
+                } => {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        self.consume_status_stacks(
+                            actor_idx,
+                            dst_idx,
+                            status_type,
+                            damage_per_stack,
+                            0,
+                            events,
+                        );
+                    }
+                }
+                EffectSpec::GrantImmunity {
+                    target,
+                    filter,
+                    duration,
+                } => {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        self.grant_immunity(actor_idx, dst_idx, filter, duration, events);
+                    }
+                }
+                EffectSpec::Heal { target, amount } => {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        let heal = self.heal_amount_for(amount, dst_idx, damage_dealt);
+                        self.heal_unit(actor_idx, dst_idx, heal, skill.name, 0, events);
+                    }
+                }
+                EffectSpec::GrantBarrier {
+                    target,
+                    amount,
+                    decay,
+                } => {
+                    if let Some(dst_idx) = self.resolve_effect_target(target, context_action) {
+                        self.grant_barrier(actor_idx, dst_idx, amount, decay, events);
                     }
                 }
             }
         }
+
+        let context_turn_end = TriggerContext {
+            trigger_type: TriggerType::OnTurnEnd,
+            ..context_turn
+        };
+        self.process_trait_triggers(context_turn_end, 0, events);
+        self.resolving_skill = None;
+    }
+
+    fn next_ready_actor(&self) -> Option<(usize, Team)> {
+        let state = self.state_ref()?;
+        let mut ready_indices: Vec<usize> = state
+            .units
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, u)| {
+                if u.is_alive() && u.action_gauge >= 100.0 {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        ready_indices.sort_by(|&a, &b| {
+            state.units[b]
+                .action_gauge
+                .partial_cmp(&state.units[a].action_gauge)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let idx = *ready_indices.first()?;
+        Some((idx, state.units[idx].team))
+    }
+
+    /// Hero whose turn is paused waiting for input.
+    pub(crate) fn waiting_hero_idx(&self) -> Option<usize> {
+        if !self.waiting_for_input {
+            return None;
+        }
+        self.next_ready_actor()
+            .filter(|(_, team)| *team == Team::Player)
+            .map(|(idx, _)| idx)
+    }
+
+    /// Living units `skill` may be aimed at when cast by `actor_idx`.
+    pub(crate) fn legal_targets(&self, actor_idx: usize, skill: &SkillSpec) -> Vec<usize> {
+        let Some(state) = self.state_ref() else {
+            return Vec::new();
+        };
+        let actor_team = state.units[actor_idx].team;
+        state
+            .units
+            .iter()
+            .enumerate()
+            .filter(|(idx, u)| {
+                u.is_alive()
+                    && match skill.targeting {
+                        SkillTargeting::Enemy => u.team != actor_team,
+                        SkillTargeting::Ally => u.team == actor_team,
+                        SkillTargeting::Caster => *idx == actor_idx,
+                    }
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Targets for the waiting hero; empty while no hero is waiting for input.
+    pub(crate) fn legal_target_ids(&self, action: ActionKind) -> Vec<u32> {
+        let Some(actor_idx) = self.waiting_hero_idx() else {
+            return Vec::new();
+        };
+        if matches!(
+            action,
+            ActionKind::NodeChoice(_) | ActionKind::PathChoice(_) | ActionKind::TraitChoice(_)
+        ) {
+            return Vec::new();
+        }
+        let skill = self.choose_skill_for_action(actor_idx, action);
+        self.legal_targets(actor_idx, skill)
+            .into_iter()
+            .map(|idx| self.unit_id_for_idx(idx))
+            .collect()
+    }
+
+    pub(crate) fn validate_target(
+        &self,
+        actor_idx: usize,
+        skill: &SkillSpec,
+        unit_id: u32,
+    ) -> Result<usize, String> {
+        let Some(target_idx) = self.unit_idx_for_id(unit_id) else {
+            return Err(format!("invalid_target:unknown_unit:{unit_id}"));
+        };
+        if self.legal_targets(actor_idx, skill).contains(&target_idx) {
+            return Ok(target_idx);
+        }
+
+        let state = self.state_ref().ok_or("invalid_target:no_battle")?;
+        let target = &state.units[target_idx];
+        let same_team = target.team == state.units[actor_idx].team;
+        if !target.is_alive() {
+            Err(format!("invalid_target:dead:{unit_id}"))
+        } else if skill.targeting == SkillTargeting::Caster {
+            Err(format!("invalid_target:self_only:{unit_id}"))
+        } else if same_team == (skill.targeting == SkillTargeting::Enemy) {
+            Err(format!("invalid_target:wrong_team:{unit_id}"))
+        } else {
+            Err(format!("invalid_target:{unit_id}"))
+        }
+    }
+
+    /// Requested target when it is still legal, otherwise a random legal one.
+    fn resolve_player_target(
+        &mut self,
+        actor_idx: usize,
+        skill: &SkillSpec,
+        target: Option<u32>,
+    ) -> Option<usize> {
+        if let Some(idx) = target.and_then(|id| self.validate_target(actor_idx, skill, id).ok()) {
+            return Some(idx);
+        }
+        let legal = self.legal_targets(actor_idx, skill);
+        if legal.is_empty() {
+            None
+        } else {
+            Some(legal[self.run.rng.range_usize(legal.len())])
+        }
     }
 
+    /// Runs `actor_idx`'s turn and returns the battle outcome if it ended the battle.
+    /// A hero whose chosen skill is not ready gets the readiness error and keeps its turn.
     fn execute_turn(
         &mut self,
         actor_idx: usize,
         action: ActionKind,
+        target: Option<u32>,
         events: &mut Vec<String>,
-    ) -> Option<&'static str> {
+    ) -> Result<Option<&'static str>, String> {
         let Some(state) = self.state_ref() else {
-            return None;
+            return Ok(None);
         };
 
         if !state.units[actor_idx].is_alive() || state.units[actor_idx].action_gauge < 100.0 {
-            return None;
+            return Ok(None);
         }
 
         let actor_team = state.units[actor_idx].team;
-        let target_team = if actor_team == Team::Player {
-            Team::Enemy
+        let chosen = if actor_team == Team::Player {
+            let skill = self.choose_skill_for_action(actor_idx, action);
+            self.check_skill_ready(actor_idx, skill)?;
+            self.resolve_player_target(actor_idx, skill, target)
+                .map(|target_idx| (target_idx, skill))
         } else {
-            Team::Player
+            self.take_enemy_intent(actor_idx, events)
         };
-
-        let Some(target_idx) = self.pick_target_index(target_team) else {
-            return None;
+        let Some((target_idx, skill)) = chosen else {
+            return Ok(None);
         };
 
         if let Some(state) = self.state_mut() {
             state.units[actor_idx].action_gauge -= 100.0;
         }
-
-        let skill = if actor_team == Team::Player {
-            self.choose_skill_for_action(action)
-        } else {
-            skill_by_id("basic_attack").unwrap_or(&BASIC_ATTACK)
-        };
+        self.commit_skill_use(actor_idx, skill);
 
         self.execute_skill(actor_idx, target_idx, skill, events);
-        self.check_and_emit_battle_end(events)
+        let outcome = self.check_and_emit_battle_end(events);
+        if outcome.is_none() && actor_team == Team::Enemy {
+            self.plan_enemy_intent(actor_idx, events);
+        }
+        Ok(outcome)
     }
 
+    #[cfg(test)]
     pub(crate) fn step_once(&mut self, dt: f32, action: Option<ActionKind>) -> StepResult {
+        self.step_once_with_target(dt, action, None)
+    }
+
+    /// `target` is the battle unit id the player's action is aimed at.
+    pub(crate) fn step_once_with_target(
+        &mut self,
+        dt: f32,
+        action: Option<ActionKind>,
+        target: Option<u32>,
+    ) -> StepResult {
+        let mut result = self.advance(dt, action, target);
+        result.input_unit_id = self.waiting_hero_idx().map(|idx| self.unit_id_for_idx(idx));
+        result
+    }
+
+    fn advance(&mut self, dt: f32, action: Option<ActionKind>, target: Option<u32>) -> StepResult {
         let mut events = Vec::new();
         set_log_tick(self.sim_tick());
 
@@ -232,6 +453,8 @@ impl ActiveRun {
             return StepResult {
                 events,
                 need_input: false,
+                input_kind: String::new(),
+                input_unit_id: None,
                 ended: true,
                 error: String::new(),
             };
@@ -241,21 +464,68 @@ impl ActiveRun {
             push_event(&mut events, Event::RunStart { seed: self.seed });
         }
 
-        self.ensure_battle_started(&mut events);
+        self.ensure_node_started(&mut events);
         if self.ended {
             return StepResult {
                 events,
                 need_input: false,
+                input_kind: String::new(),
+                input_unit_id: None,
                 ended: true,
                 error: String::new(),
             };
         }
 
+        if self.pending_node.is_some() || self.awaiting_path || !self.pending_traits.is_empty() {
+            return self.step_pending_choice(action, events);
+        }
+
+        if let Some(
+            ActionKind::NodeChoice(_) | ActionKind::PathChoice(_) | ActionKind::TraitChoice(_),
+        ) = action
+        {
+            return StepResult {
+                events,
+                need_input: self.waiting_for_input,
+                input_kind: self.pending_input_kind().to_string(),
+                input_unit_id: None,
+                ended: false,
+                error: "invalid_action:choice_outside_node".to_string(),
+            };
+        }
+
+        if let Some(action) = action {
+            let checked = match self.waiting_hero_idx() {
+                Some(actor_idx) => {
+                    let skill = self.choose_skill_for_action(actor_idx, action);
+                    self.check_skill_ready(actor_idx, skill).and_then(|()| {
+                        target.map_or(Ok(()), |unit_id| {
+                            self.validate_target(actor_idx, skill, unit_id).map(|_| ())
+                        })
+                    })
+                }
+                None => Err("invalid_action:no_hero_waiting".to_string()),
+            };
+            if let Err(error) = checked {
+                return StepResult {
+                    events,
+                    need_input: self.waiting_for_input,
+                    input_kind: self.pending_input_kind().to_string(),
+                    input_unit_id: None,
+                    ended: false,
+                    error,
+                };
+            }
+        }
+
         let mut queued_action = action;
+        let mut queued_target = target;
         if self.waiting_for_input && queued_action.is_none() {
             return StepResult {
                 events,
                 need_input: true,
+                input_kind: "action".to_string(),
+                input_unit_id: None,
                 ended: false,
                 error: String::new(),
             };
@@ -263,11 +533,16 @@ impl ActiveRun {
 
         let mut remaining = dt.max(0.0);
         let mut need_input = false;
+        let mut error = String::new();
 
         while remaining > 0.0 || (self.waiting_for_input && queued_action.is_some()) {
             let current_tick = self.advance_sim_tick();
             set_log_tick(current_tick);
-            let step_dt = if remaining > 0.0 { remaining.min(0.1) } else { 0.0 };
+            let step_dt = if remaining > 0.0 {
+                remaining.min(0.1)
+            } else {
+                0.0
+            };
             remaining = (remaining - step_dt).max(0.0);
             self.elapsed_time += step_dt;
 
@@ -279,50 +554,26 @@ impl ActiveRun {
             if step_dt > 0.0 {
                 let unit_count = self.state_ref().map(|s| s.units.len()).unwrap_or(0);
                 for unit_idx in 0..unit_count {
-                    let speed_mult = self.gauge_speed_multiplier(unit_idx);
+                    let speed = self.effective_stat(unit_idx, StatType::Speed)
+                        * self.gauge_speed_multiplier(unit_idx);
                     if let Some(state) = self.state_mut() {
                         if state.units[unit_idx].is_alive() {
-                            state.units[unit_idx].action_gauge +=
-                                state.units[unit_idx].speed * step_dt * speed_mult;
+                            state.units[unit_idx].action_gauge += speed * step_dt;
                         }
                     }
                 }
             }
 
-            loop {
-                let Some((actor_idx, actor_team)) = ({
-                    if let Some(state) = self.state_ref() {
-                        let mut ready_indices: Vec<usize> = state
-                            .units
-                            .iter()
-                            .enumerate()
-                            .filter_map(|(idx, u)| {
-                                if u.is_alive() && u.action_gauge >= 100.0 {
-                                    Some(idx)
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-
-                        if ready_indices.is_empty() {
-                            None
-                        } else {
-                            ready_indices.sort_by(|&a, &b| {
-                                state.units[b]
-                                    .action_gauge
-                                    .partial_cmp(&state.units[a].action_gauge)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            });
-                            let idx = ready_indices[0];
-                            Some((idx, state.units[idx].team))
-                        }
-                    } else {
-                        None
+            while let Some((actor_idx, actor_team)) = self.next_ready_actor() {
+                if self.skip_stunned_turn(actor_idx, &mut events) {
+                    if actor_team == Team::Player && self.waiting_for_input {
+                        // Input already given was meant for the stunned hero.
+                        self.waiting_for_input = false;
+                        queued_action = None;
+                        queued_target = None;
                     }
-                }) else {
-                    break;
-                };
+                    continue;
+                }
 
                 if actor_team == Team::Player {
                     if queued_action.is_none() {
@@ -334,12 +585,23 @@ impl ActiveRun {
                     let action_kind = queued_action.take().unwrap_or(ActionKind::BasicAttack);
                     self.waiting_for_input = false;
 
-                    if let Some(outcome) = self.execute_turn(actor_idx, action_kind, &mut events) {
-                        self.finalize_battle(outcome, &mut events);
-                        break;
+                    let target = queued_target.take();
+                    match self.execute_turn(actor_idx, action_kind, target, &mut events) {
+                        Ok(Some(outcome)) => {
+                            self.finalize_battle(outcome, &mut events);
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(rejected) => {
+                            // Another unit acted first and the hero's skill is no longer ready.
+                            error = rejected;
+                            need_input = true;
+                            self.waiting_for_input = true;
+                            break;
+                        }
                     }
-                } else if let Some(outcome) =
-                    self.execute_turn(actor_idx, ActionKind::BasicAttack, &mut events)
+                } else if let Ok(Some(outcome)) =
+                    self.execute_turn(actor_idx, ActionKind::BasicAttack, None, &mut events)
                 {
                     self.finalize_battle(outcome, &mut events);
                     break;
@@ -356,14 +618,69 @@ impl ActiveRun {
         }
 
         if self.current_battle.is_none() && !self.ended {
-            self.ensure_battle_started(&mut events);
+            self.ensure_node_started(&mut events);
+        }
+
+        let input_kind = self.pending_input_kind();
+        StepResult {
+            events,
+            need_input: need_input || !input_kind.is_empty(),
+            input_kind: input_kind.to_string(),
+            input_unit_id: None,
+            ended: self.ended,
+            error,
+        }
+    }
+
+    /// Which kind of input the run is currently blocked on, if any.
+    pub(crate) fn pending_input_kind(&self) -> &'static str {
+        if self.ended {
+            ""
+        } else if !self.pending_traits.is_empty() {
+            "trait_choice"
+        } else if self.pending_node.is_some() {
+            "node_choice"
+        } else if self.awaiting_path {
+            "path_choice"
+        } else if self.waiting_for_input {
+            "action"
+        } else {
+            ""
         }
+    }
+
+    fn step_pending_choice(
+        &mut self,
+        action: Option<ActionKind>,
+        mut events: Vec<String>,
+    ) -> StepResult {
+        let result = match action {
+            Some(ActionKind::TraitChoice(index)) if !self.pending_traits.is_empty() => {
+                self.resolve_trait_choice(index, &mut events)
+            }
+            Some(ActionKind::NodeChoice(index)) if self.pending_node.is_some() => {
+                self.resolve_node_choice(index, &mut events)
+            }
+            Some(ActionKind::PathChoice(node_id)) if self.awaiting_path => {
+                self.resolve_path_choice(node_id, &mut events)
+            }
+            Some(_) => Err(format!(
+                "invalid_action:expected_{}",
+                self.pending_input_kind()
+            )),
+            None => Ok(()),
+        };
+
+        self.ensure_node_started(&mut events);
+        let input_kind = self.pending_input_kind();
 
         StepResult {
             events,
-            need_input,
+            need_input: !input_kind.is_empty(),
+            input_kind: input_kind.to_string(),
+            input_unit_id: None,
             ended: self.ended,
-            error: String::new(),
+            error: result.err().unwrap_or_default(),
         }
     }
 }
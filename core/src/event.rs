@@ -1,3 +1,5 @@
+use crate::engine::damage_system::DamageStep;
+
 /// Structured run event emitted from Rust and consumed by JS UI.
 pub enum Event {
     RunStart {
@@ -6,6 +8,7 @@ pub enum Event {
     NodeStart {
         node_index: u32,
         node_type: &'static str,
+        floor: u32,
     },
     BattleStart {
         battle_index: u32,
@@ -13,32 +16,126 @@ pub enum Event {
     },
     TurnReady {
         actor: &'static str,
+        actor_id: u32,
+    },
+    TurnSkipped {
+        actor: &'static str,
+        actor_id: u32,
+        reason: &'static str,
     },
     ActionUsed {
         actor: &'static str,
+        actor_id: u32,
         action_name: &'static str,
     },
+    /// `amount` is the whole hit: `absorbed` by the barrier plus `hp_damage`.
     DamageDealt {
         src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        damage_type: &'static str,
+        amount: f32,
+        absorbed: f32,
+        hp_damage: f32,
+        dst_hp_after: f32,
+        crit: bool,
+        /// Failed the accuracy roll; every amount is zero.
+        miss: bool,
+        /// Damage pipeline steps in stage order; empty for misses.
+        breakdown: Vec<DamageStep>,
+    },
+    /// `amount` is the HP actually restored; the rest of the heal is `overheal`.
+    Healed {
+        src: &'static str,
+        src_id: u32,
         dst: &'static str,
+        dst_id: u32,
+        /// Skill, trait or status name, or `Lifesteal`.
+        source: &'static str,
         amount: f32,
+        overheal: f32,
         dst_hp_after: f32,
     },
+    BarrierGranted {
+        src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        amount: f32,
+        barrier_after: f32,
+    },
     StatusApplied {
         src: &'static str,
+        src_id: u32,
         dst: &'static str,
+        dst_id: u32,
         status: &'static str,
+        /// Total stacks on the target after the application.
         stacks: u32,
+        /// Stacks this application brought, before caps.
+        added: u32,
         duration: u32,
     },
     StatusTick {
+        /// Unit that applied the ticking status.
+        src: &'static str,
+        src_id: u32,
         dst: &'static str,
+        dst_id: u32,
         status: &'static str,
+        damage_type: &'static str,
+        /// Tick damage after mitigation, split into `absorbed` and `hp_damage`.
         amount: f32,
+        absorbed: f32,
+        hp_damage: f32,
         dst_hp_after: f32,
     },
+    /// A decaying status lost one stack instead of expiring.
+    StatusDecayed {
+        dst: &'static str,
+        dst_id: u32,
+        status: &'static str,
+        stacks: u32,
+    },
+    StatusCleansed {
+        src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        status: &'static str,
+        stacks: u32,
+    },
+    /// Stacks stripped by a consume effect and the damage they paid out.
+    StatusConsumed {
+        src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        status: &'static str,
+        stacks: u32,
+        damage: f32,
+    },
+    ImmunityGranted {
+        src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        /// Status name or category (`buff`/`debuff`).
+        filter: &'static str,
+        duration: f32,
+    },
+    /// An application stopped by immunity.
+    StatusBlocked {
+        src: &'static str,
+        src_id: u32,
+        dst: &'static str,
+        dst_id: u32,
+        status: &'static str,
+    },
     StatusExpired {
         dst: &'static str,
+        dst_id: u32,
         status: &'static str,
     },
     BattleEnd {
@@ -57,6 +154,35 @@ pub enum Event {
         trait_name: &'static str,
         effect_summary: String,
     },
+    TraitOffered {
+        choice_count: u32,
+    },
+    TraitAcquired {
+        trait_name: &'static str,
+        level: u32,
+        trait_count: u32,
+    },
+    NodeOffered {
+        title: &'static str,
+        choice_count: u32,
+    },
+    NodeChoiceMade {
+        choice: &'static str,
+        effect_summary: String,
+    },
+    GoldChanged {
+        delta: i32,
+        gold_after: u32,
+    },
+    IntentChanged {
+        actor: &'static str,
+        actor_id: u32,
+        skill_id: &'static str,
+        skill_name: &'static str,
+        target: &'static str,
+        target_id: u32,
+        estimated_damage: f32,
+    },
 }
 
 impl Event {
@@ -68,11 +194,13 @@ impl Event {
             Event::NodeStart {
                 node_index,
                 node_type,
+                floor,
             } => {
                 format!(
-                    r#"{{"kind":"NodeStart","node_index":{},"node_type":"{}"}}"#,
+                    r#"{{"kind":"NodeStart","node_index":{},"node_type":"{}","floor":{}}}"#,
                     node_index,
-                    escape_json(node_type)
+                    escape_json(node_type),
+                    floor
                 )
             }
             Event::BattleStart {
@@ -85,67 +213,250 @@ impl Event {
                     escape_json(enemy_name)
                 )
             }
-            Event::TurnReady { actor } => {
+            Event::TurnReady { actor, actor_id } => {
+                format!(
+                    r#"{{"kind":"TurnReady","actor":"{}","actor_id":{}}}"#,
+                    escape_json(actor),
+                    actor_id
+                )
+            }
+            Event::TurnSkipped {
+                actor,
+                actor_id,
+                reason,
+            } => {
                 format!(
-                    r#"{{"kind":"TurnReady","actor":"{}"}}"#,
-                    escape_json(actor)
+                    r#"{{"kind":"TurnSkipped","actor":"{}","actor_id":{},"reason":"{}"}}"#,
+                    escape_json(actor),
+                    actor_id,
+                    escape_json(reason)
                 )
             }
-            Event::ActionUsed { actor, action_name } => {
+            Event::ActionUsed {
+                actor,
+                actor_id,
+                action_name,
+            } => {
                 format!(
-                    r#"{{"kind":"ActionUsed","actor":"{}","action_name":"{}"}}"#,
+                    r#"{{"kind":"ActionUsed","actor":"{}","actor_id":{},"action_name":"{}"}}"#,
                     escape_json(actor),
+                    actor_id,
                     escape_json(action_name)
                 )
             }
             Event::DamageDealt {
                 src,
+                src_id,
                 dst,
+                dst_id,
+                damage_type,
                 amount,
+                absorbed,
+                hp_damage,
                 dst_hp_after,
+                crit,
+                miss,
+                breakdown,
             } => {
                 format!(
-                    r#"{{"kind":"DamageDealt","src":"{}","dst":"{}","amount":{},"dst_hp_after":{}}}"#,
+                    r#"{{"kind":"DamageDealt","src":"{}","src_id":{},"dst":"{}","dst_id":{},"damage_type":"{}","amount":{},"absorbed":{},"hp_damage":{},"dst_hp_after":{},"crit":{},"miss":{},"breakdown":[{}]}}"#,
                     escape_json(src),
+                    src_id,
                     escape_json(dst),
+                    dst_id,
+                    escape_json(damage_type),
                     json_f32(*amount),
+                    json_f32(*absorbed),
+                    json_f32(*hp_damage),
+                    json_f32(*dst_hp_after),
+                    crit,
+                    miss,
+                    json_damage_steps(breakdown)
+                )
+            }
+            Event::Healed {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                source,
+                amount,
+                overheal,
+                dst_hp_after,
+            } => {
+                format!(
+                    r#"{{"kind":"Healed","src":"{}","src_id":{},"dst":"{}","dst_id":{},"source":"{}","amount":{},"overheal":{},"dst_hp_after":{}}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(source),
+                    json_f32(*amount),
+                    json_f32(*overheal),
                     json_f32(*dst_hp_after)
                 )
             }
+            Event::BarrierGranted {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                amount,
+                barrier_after,
+            } => {
+                format!(
+                    r#"{{"kind":"BarrierGranted","src":"{}","src_id":{},"dst":"{}","dst_id":{},"amount":{},"barrier_after":{}}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    json_f32(*amount),
+                    json_f32(*barrier_after)
+                )
+            }
             Event::StatusApplied {
                 src,
+                src_id,
                 dst,
+                dst_id,
                 status,
                 stacks,
+                added,
                 duration,
             } => {
                 format!(
-                    r#"{{"kind":"StatusApplied","src":"{}","dst":"{}","status":"{}","stacks":{},"duration":{}}}"#,
+                    r#"{{"kind":"StatusApplied","src":"{}","src_id":{},"dst":"{}","dst_id":{},"status":"{}","stacks":{},"added":{},"duration":{}}}"#,
                     escape_json(src),
+                    src_id,
                     escape_json(dst),
+                    dst_id,
                     escape_json(status),
                     stacks,
+                    added,
                     duration
                 )
             }
+            Event::StatusDecayed {
+                dst,
+                dst_id,
+                status,
+                stacks,
+            } => {
+                format!(
+                    r#"{{"kind":"StatusDecayed","dst":"{}","dst_id":{},"status":"{}","stacks":{}}}"#,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(status),
+                    stacks
+                )
+            }
             Event::StatusTick {
+                src,
+                src_id,
                 dst,
+                dst_id,
                 status,
+                damage_type,
                 amount,
+                absorbed,
+                hp_damage,
                 dst_hp_after,
             } => {
                 format!(
-                    r#"{{"kind":"StatusTick","dst":"{}","status":"{}","amount":{},"dst_hp_after":{}}}"#,
+                    r#"{{"kind":"StatusTick","src":"{}","src_id":{},"dst":"{}","dst_id":{},"status":"{}","damage_type":"{}","amount":{},"absorbed":{},"hp_damage":{},"dst_hp_after":{}}}"#,
+                    escape_json(src),
+                    src_id,
                     escape_json(dst),
+                    dst_id,
                     escape_json(status),
+                    escape_json(damage_type),
                     json_f32(*amount),
+                    json_f32(*absorbed),
+                    json_f32(*hp_damage),
                     json_f32(*dst_hp_after)
                 )
             }
-            Event::StatusExpired { dst, status } => {
+            Event::StatusCleansed {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                status,
+                stacks,
+            } => {
+                format!(
+                    r#"{{"kind":"StatusCleansed","src":"{}","src_id":{},"dst":"{}","dst_id":{},"status":"{}","stacks":{}}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(status),
+                    stacks
+                )
+            }
+            Event::StatusConsumed {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                status,
+                stacks,
+                damage,
+            } => {
+                format!(
+                    r#"{{"kind":"StatusConsumed","src":"{}","src_id":{},"dst":"{}","dst_id":{},"status":"{}","stacks":{},"damage":{}}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(status),
+                    stacks,
+                    json_f32(*damage)
+                )
+            }
+            Event::ImmunityGranted {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                filter,
+                duration,
+            } => {
+                format!(
+                    r#"{{"kind":"ImmunityGranted","src":"{}","src_id":{},"dst":"{}","dst_id":{},"filter":"{}","duration":{}}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(filter),
+                    json_f32(*duration)
+                )
+            }
+            Event::StatusBlocked {
+                src,
+                src_id,
+                dst,
+                dst_id,
+                status,
+            } => {
+                format!(
+                    r#"{{"kind":"StatusBlocked","src":"{}","src_id":{},"dst":"{}","dst_id":{},"status":"{}"}}"#,
+                    escape_json(src),
+                    src_id,
+                    escape_json(dst),
+                    dst_id,
+                    escape_json(status)
+                )
+            }
+            Event::StatusExpired {
+                dst,
+                dst_id,
+                status,
+            } => {
                 format!(
-                    r#"{{"kind":"StatusExpired","dst":"{}","status":"{}"}}"#,
+                    r#"{{"kind":"StatusExpired","dst":"{}","dst_id":{},"status":"{}"}}"#,
                     escape_json(dst),
+                    dst_id,
                     escape_json(status)
                 )
             }
@@ -189,6 +500,70 @@ impl Event {
                     escape_json(effect_summary)
                 )
             }
+            Event::TraitOffered { choice_count } => {
+                format!(
+                    r#"{{"kind":"TraitOffered","choice_count":{}}}"#,
+                    choice_count
+                )
+            }
+            Event::TraitAcquired {
+                trait_name,
+                level,
+                trait_count,
+            } => {
+                format!(
+                    r#"{{"kind":"TraitAcquired","trait_name":"{}","level":{},"trait_count":{}}}"#,
+                    escape_json(trait_name),
+                    level,
+                    trait_count
+                )
+            }
+            Event::NodeOffered {
+                title,
+                choice_count,
+            } => {
+                format!(
+                    r#"{{"kind":"NodeOffered","title":"{}","choice_count":{}}}"#,
+                    escape_json(title),
+                    choice_count
+                )
+            }
+            Event::NodeChoiceMade {
+                choice,
+                effect_summary,
+            } => {
+                format!(
+                    r#"{{"kind":"NodeChoiceMade","choice":"{}","effect_summary":"{}"}}"#,
+                    escape_json(choice),
+                    escape_json(effect_summary)
+                )
+            }
+            Event::GoldChanged { delta, gold_after } => {
+                format!(
+                    r#"{{"kind":"GoldChanged","delta":{},"gold_after":{}}}"#,
+                    delta, gold_after
+                )
+            }
+            Event::IntentChanged {
+                actor,
+                actor_id,
+                skill_id,
+                skill_name,
+                target,
+                target_id,
+                estimated_damage,
+            } => {
+                format!(
+                    r#"{{"kind":"IntentChanged","actor":"{}","actor_id":{},"skill_id":"{}","skill_name":"{}","target":"{}","target_id":{},"estimated_damage":{}}}"#,
+                    escape_json(actor),
+                    actor_id,
+                    escape_json(skill_id),
+                    escape_json(skill_name),
+                    escape_json(target),
+                    target_id,
+                    json_f32(*estimated_damage)
+                )
+            }
         }
     }
 }
@@ -203,3 +578,19 @@ fn escape_json(input: &str) -> String {
 fn json_f32(v: f32) -> String {
     format!("{:.2}", v)
 }
+
+fn json_damage_steps(steps: &[DamageStep]) -> String {
+    steps
+        .iter()
+        .map(|step| {
+            format!(
+                r#"{{"stage":"{}","source":"{}","value":{},"amount":{}}}"#,
+                step.stage.as_str(),
+                escape_json(step.source),
+                json_f32(step.value),
+                json_f32(step.amount)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
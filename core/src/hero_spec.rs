@@ -0,0 +1,74 @@
+use crate::skill::{
+    SkillId, ARC_JOLT, DETONATE, EMBER_LASH, FROST_BITE, MEND, PURIFY, RUIN_STRIKE,
+};
+
+pub type HeroId = &'static str;
+
+pub const MAX_PARTY_SIZE: usize = 3;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct HeroSpec {
+    pub id: HeroId,
+    pub name: &'static str,
+    pub max_hp: f32,
+    pub atk: i32,
+    pub speed: f32,
+    /// Skills bound to action slots 0..=3, in slot order.
+    pub skills: &'static [SkillId],
+}
+
+pub const VANGUARD: HeroSpec = HeroSpec {
+    id: "vanguard",
+    name: "Vanguard",
+    max_hp: 140.0,
+    atk: 17,
+    speed: 35.0,
+    skills: &[EMBER_LASH.id, FROST_BITE.id, ARC_JOLT.id, RUIN_STRIKE.id],
+};
+
+pub const ARCANIST: HeroSpec = HeroSpec {
+    id: "arcanist",
+    name: "Arcanist",
+    max_hp: 105.0,
+    atk: 14,
+    speed: 39.0,
+    skills: &[FROST_BITE.id, ARC_JOLT.id, EMBER_LASH.id, DETONATE.id],
+};
+
+pub const REAVER: HeroSpec = HeroSpec {
+    id: "reaver",
+    name: "Reaver",
+    max_hp: 160.0,
+    atk: 15,
+    speed: 30.0,
+    skills: &[RUIN_STRIKE.id, EMBER_LASH.id, MEND.id, PURIFY.id],
+};
+
+pub const DEFAULT_PARTY: [HeroId; 1] = [VANGUARD.id];
+
+pub const SELECTABLE_HEROES: [HeroId; 3] = [VANGUARD.id, ARCANIST.id, REAVER.id];
+
+pub fn hero_by_id(id: &str) -> Option<&'static HeroSpec> {
+    match id {
+        "vanguard" => Some(&VANGUARD),
+        "arcanist" => Some(&ARCANIST),
+        "reaver" => Some(&REAVER),
+        _ => None,
+    }
+}
+
+pub fn selectable_hero_ids() -> Vec<String> {
+    SELECTABLE_HEROES
+        .iter()
+        .map(|id| (*id).to_string())
+        .collect()
+}
+
+pub fn selectable_hero_names() -> Vec<String> {
+    SELECTABLE_HEROES
+        .iter()
+        .filter_map(|id| hero_by_id(id))
+        .map(|spec| spec.name.to_string())
+        .collect()
+}
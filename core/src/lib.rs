@@ -1,11 +1,16 @@
 mod battle;
+mod enemy_spec;
 mod engine;
 mod event;
+mod hero_spec;
 mod log;
+mod map;
 mod model;
+mod node_spec;
 mod rng;
 mod run;
 mod skill;
+mod status_spec;
 mod step_api;
 mod trait_spec;
 
@@ -0,0 +1,217 @@
+use crate::model::NodeType;
+use crate::rng::SimpleRng;
+
+pub const ACT_COUNT: u32 = 2;
+/// Floors per act, including the boss floor at the end of each act.
+pub const FLOORS_PER_ACT: u32 = 6;
+pub const MIN_LANES: u32 = 2;
+pub const MAX_LANES: u32 = 3;
+
+/// Relative weights for regular (non-entry, non-boss) floors.
+const NODE_TYPE_WEIGHTS: [(NodeType, u32); 4] = [
+    (NodeType::Battle, 50),
+    (NodeType::Event, 22),
+    (NodeType::Shop, 10),
+    (NodeType::Rest, 18),
+];
+
+/// Chance (in percent) that a node also links to the lane next to its main link.
+const EXTRA_EDGE_PERCENT: u32 = 40;
+
+#[derive(Clone, Debug)]
+pub struct MapNode {
+    pub id: u32,
+    pub act: u32,
+    pub floor: u32,
+    pub lane: u32,
+    pub node_type: NodeType,
+    pub next: Vec<u32>,
+}
+
+/// Layered run map. Floors are 1-based and nodes only link to the following floor.
+#[derive(Clone, Debug)]
+pub struct RunMap {
+    pub nodes: Vec<MapNode>,
+}
+
+impl RunMap {
+    /// Map of the run started with `seed`. It rolls on its own stream split off the seed,
+    /// so both run entry points build the same map and map rolls never shift combat rolls.
+    pub fn for_seed(seed: u64) -> Self {
+        /// Mixed into the run seed for the map generator's stream.
+        const MAP_SEED_SALT: u64 = 0x6D61_705F_7365_6564;
+        Self::generate(&mut SimpleRng::new(seed ^ MAP_SEED_SALT))
+    }
+
+    /// Builds `ACT_COUNT` acts of branching floors, each ending in a single boss node.
+    /// The very first floor is a single battle so every run opens with a fight.
+    pub fn generate(rng: &mut SimpleRng) -> Self {
+        let mut nodes: Vec<MapNode> = Vec::new();
+        let mut prev_floor: Vec<u32> = Vec::new();
+
+        for act in 1..=ACT_COUNT {
+            for floor_in_act in 1..=FLOORS_PER_ACT {
+                let floor = (act - 1) * FLOORS_PER_ACT + floor_in_act;
+                let is_boss = floor_in_act == FLOORS_PER_ACT;
+                let lane_count = if is_boss || floor == 1 {
+                    1
+                } else {
+                    MIN_LANES + rng.range_usize((MAX_LANES - MIN_LANES + 1) as usize) as u32
+                };
+
+                let mut current_floor = Vec::new();
+                for lane in 0..lane_count {
+                    let node_type = if is_boss {
+                        NodeType::Boss
+                    } else if floor_in_act == 1 {
+                        NodeType::Battle
+                    } else if floor_in_act == FLOORS_PER_ACT - 1 {
+                        NodeType::Rest
+                    } else {
+                        roll_node_type(rng)
+                    };
+                    let id = nodes.len() as u32;
+                    nodes.push(MapNode {
+                        id,
+                        act,
+                        floor,
+                        lane,
+                        node_type,
+                        next: Vec::new(),
+                    });
+                    current_floor.push(id);
+                }
+
+                link_floors(&mut nodes, &prev_floor, &current_floor, rng);
+                prev_floor = current_floor;
+            }
+        }
+
+        Self { nodes }
+    }
+
+    pub fn node(&self, id: u32) -> Option<&MapNode> {
+        self.nodes.get(id as usize)
+    }
+
+    pub fn start_ids(&self) -> Vec<u32> {
+        self.nodes
+            .iter()
+            .filter(|n| n.floor == 1)
+            .map(|n| n.id)
+            .collect()
+    }
+
+    pub fn floor_count(&self) -> u32 {
+        self.nodes.iter().map(|n| n.floor).max().unwrap_or(0)
+    }
+}
+
+fn roll_node_type(rng: &mut SimpleRng) -> NodeType {
+    let total: u32 = NODE_TYPE_WEIGHTS.iter().map(|(_, w)| *w).sum();
+    let mut roll = rng.range_usize(total as usize) as u32;
+    for (node_type, weight) in NODE_TYPE_WEIGHTS {
+        if roll < weight {
+            return node_type;
+        }
+        roll -= weight;
+    }
+    NodeType::Battle
+}
+
+/// Lane of `to` closest to the relative position of lane `idx` out of `from_len`.
+fn proportional_lane(idx: usize, from_len: usize, to_len: usize) -> usize {
+    if from_len <= 1 || to_len <= 1 {
+        return 0;
+    }
+    (idx * (to_len - 1) + (from_len - 1) / 2) / (from_len - 1)
+}
+
+/// Links two consecutive floors, making sure every node on `to` is reachable.
+fn link_floors(nodes: &mut [MapNode], from: &[u32], to: &[u32], rng: &mut SimpleRng) {
+    if from.is_empty() || to.is_empty() {
+        return;
+    }
+
+    for (i, src) in from.iter().enumerate() {
+        let mut targets = Vec::new();
+        if from.len() == 1 {
+            targets.extend(to.iter().copied());
+        } else {
+            let main = proportional_lane(i, from.len(), to.len());
+            targets.push(to[main]);
+            if (rng.range_usize(100) as u32) < EXTRA_EDGE_PERCENT {
+                let side = if i * 2 < from.len() {
+                    main + 1
+                } else {
+                    main.wrapping_sub(1)
+                };
+                if let Some(extra) = to.get(side) {
+                    targets.push(*extra);
+                }
+            }
+        }
+        let next = &mut nodes[*src as usize].next;
+        for target in targets {
+            if !next.contains(&target) {
+                next.push(target);
+            }
+        }
+    }
+
+    for (j, dst) in to.iter().enumerate() {
+        let reachable = from
+            .iter()
+            .any(|src| nodes[*src as usize].next.contains(dst));
+        if !reachable {
+            let src = from[proportional_lane(j, to.len(), from.len())];
+            nodes[src as usize].next.push(*dst);
+        }
+    }
+
+    for src in from {
+        nodes[*src as usize].next.sort_unstable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunMap, ACT_COUNT, FLOORS_PER_ACT};
+    use crate::model::NodeType;
+    use crate::rng::SimpleRng;
+
+    #[test]
+    fn generated_map_is_seeded_connected_and_ends_acts_with_boss() {
+        let map = RunMap::generate(&mut SimpleRng::new(31));
+        let again = RunMap::generate(&mut SimpleRng::new(31));
+        let types: Vec<NodeType> = map.nodes.iter().map(|n| n.node_type).collect();
+        let types_again: Vec<NodeType> = again.nodes.iter().map(|n| n.node_type).collect();
+        assert_eq!(types, types_again);
+
+        assert_eq!(map.floor_count(), ACT_COUNT * FLOORS_PER_ACT);
+        assert_eq!(map.start_ids().len(), 1);
+
+        for act in 1..=ACT_COUNT {
+            let boss_floor = act * FLOORS_PER_ACT;
+            let bosses: Vec<_> = map.nodes.iter().filter(|n| n.floor == boss_floor).collect();
+            assert_eq!(bosses.len(), 1);
+            assert_eq!(bosses[0].node_type, NodeType::Boss);
+        }
+
+        for node in &map.nodes {
+            if node.floor > 1 {
+                assert!(
+                    map.nodes.iter().any(|n| n.next.contains(&node.id)),
+                    "node {} is unreachable",
+                    node.id
+                );
+            }
+            if node.floor < map.floor_count() {
+                assert!(!node.next.is_empty(), "node {} is a dead end", node.id);
+            }
+            for next in &node.next {
+                assert_eq!(map.node(*next).map(|n| n.floor), Some(node.floor + 1));
+            }
+        }
+    }
+}
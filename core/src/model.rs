@@ -1,6 +1,5 @@
 /// High-level map node categories for a run.
-/// For now, only `Battle` and `Boss` are executed by the skeleton loop.
-#[allow(dead_code)]
+/// `Battle` and `Boss` run combat; `Event`, `Shop` and `Rest` pause for a choice.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NodeType {
     Battle,
@@ -10,6 +9,22 @@ pub enum NodeType {
     Boss,
 }
 
+impl NodeType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NodeType::Battle => "Battle",
+            NodeType::Event => "Event",
+            NodeType::Shop => "Shop",
+            NodeType::Rest => "Rest",
+            NodeType::Boss => "Boss",
+        }
+    }
+
+    pub fn is_combat(self) -> bool {
+        matches!(self, NodeType::Battle | NodeType::Boss)
+    }
+}
+
 /// Simple two-side team marker used in battle targeting.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Team {
@@ -23,6 +38,11 @@ pub enum Team {
 pub struct Unit {
     pub id: u32,
     pub team: Team,
+    pub name: &'static str,
+    /// Bestiary entry this unit was spawned from; `None` for heroes.
+    pub enemy_id: Option<crate::enemy_spec::EnemyId>,
+    /// Index into `RunState::party` for heroes; `None` for enemies.
+    pub party_slot: Option<usize>,
     pub hp: f32,
     pub max_hp: f32,
     pub atk: i32,
@@ -43,6 +63,32 @@ pub struct BattleState {
     pub tick: u32,
 }
 
+/// One party member, carried between battles.
+#[derive(Clone, Debug)]
+pub struct HeroState {
+    pub hero_id: crate::hero_spec::HeroId,
+    pub name: &'static str,
+    pub hp: f32,
+    pub max_hp: f32,
+    pub atk: i32,
+    pub speed: f32,
+    pub skills: &'static [crate::skill::SkillId],
+}
+
+impl HeroState {
+    pub fn from_spec(spec: &crate::hero_spec::HeroSpec) -> Self {
+        Self {
+            hero_id: spec.id,
+            name: spec.name,
+            hp: spec.max_hp,
+            max_hp: spec.max_hp,
+            atk: spec.atk,
+            speed: spec.speed,
+            skills: spec.skills,
+        }
+    }
+}
+
 /// Full run state placeholder. Keeps RNG and run progression fields.
 #[allow(dead_code)]
 pub struct RunState {
@@ -51,10 +97,8 @@ pub struct RunState {
     pub floor: u32,
     pub stage: u32,
     pub meta_placeholder: u32,
-    pub player_hp: f32,
-    pub player_max_hp: f32,
-    pub player_atk: i32,
-    pub player_speed: f32,
+    pub party: Vec<HeroState>,
+    pub gold: u32,
 }
 
 impl RunState {
@@ -65,11 +109,24 @@ impl RunState {
             floor: 1,
             stage: 0,
             meta_placeholder: 0,
-            player_hp: 140.0,
-            player_max_hp: 140.0,
-            player_atk: 17,
-            player_speed: 35.0,
+            party: crate::hero_spec::DEFAULT_PARTY
+                .iter()
+                .filter_map(|id| crate::hero_spec::hero_by_id(id))
+                .map(HeroState::from_spec)
+                .collect(),
+            gold: 0,
+        }
+    }
+
+    /// Heals every hero (fallen ones included) by `ratio` of max HP; returns the total healed.
+    pub fn recover_party(&mut self, ratio: f32) -> f32 {
+        let mut healed = 0.0;
+        for hero in &mut self.party {
+            let before = hero.hp;
+            hero.hp = crate::step_api::hp2((hero.hp + hero.max_hp * ratio).min(hero.max_hp));
+            healed += hero.hp - before;
         }
+        healed
     }
 }
 
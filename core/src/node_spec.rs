@@ -0,0 +1,131 @@
+pub type NodeChoiceId = &'static str;
+
+pub const BATTLE_GOLD_REWARD: u32 = 18;
+pub const BOSS_GOLD_REWARD: u32 = 50;
+pub const SHOP_OFFER_COUNT: usize = 3;
+
+/// Stat and HP effects apply to every party member.
+#[derive(Clone, Copy, Debug)]
+pub enum NodeEffect {
+    HealRatio(f32),
+    LoseHpRatio(f32),
+    GainGold(u32),
+    AddMaxHp(f32),
+    AddAttack(i32),
+    AddSpeed(f32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NodeChoiceSpec {
+    pub id: NodeChoiceId,
+    pub label: &'static str,
+    pub gold_cost: u32,
+    pub effects: &'static [NodeEffect],
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EventSpec {
+    pub name: &'static str,
+    pub choices: &'static [NodeChoiceSpec],
+}
+
+pub const LEAVE: NodeChoiceSpec = NodeChoiceSpec {
+    id: "leave",
+    label: "Leave",
+    gold_cost: 0,
+    effects: &[],
+};
+
+const SCRAP_CACHE_CHOICES: [NodeChoiceSpec; 2] = [
+    NodeChoiceSpec {
+        id: "salvage",
+        label: "Salvage the cache",
+        gold_cost: 0,
+        effects: &[NodeEffect::GainGold(25)],
+    },
+    LEAVE,
+];
+
+const BLOOD_ALTAR_CHOICES: [NodeChoiceSpec; 2] = [
+    NodeChoiceSpec {
+        id: "offer_blood",
+        label: "Offer blood",
+        gold_cost: 0,
+        effects: &[NodeEffect::LoseHpRatio(0.15), NodeEffect::AddAttack(3)],
+    },
+    NodeChoiceSpec {
+        id: "pray",
+        label: "Pray",
+        gold_cost: 0,
+        effects: &[NodeEffect::HealRatio(0.10)],
+    },
+];
+
+const OVERCLOCK_TERMINAL_CHOICES: [NodeChoiceSpec; 2] = [
+    NodeChoiceSpec {
+        id: "overclock",
+        label: "Overclock servos",
+        gold_cost: 0,
+        effects: &[NodeEffect::LoseHpRatio(0.10), NodeEffect::AddSpeed(4.0)],
+    },
+    LEAVE,
+];
+
+pub const SCRAP_CACHE: EventSpec = EventSpec {
+    name: "Scrap Cache",
+    choices: &SCRAP_CACHE_CHOICES,
+};
+
+pub const BLOOD_ALTAR: EventSpec = EventSpec {
+    name: "Blood Altar",
+    choices: &BLOOD_ALTAR_CHOICES,
+};
+
+pub const OVERCLOCK_TERMINAL: EventSpec = EventSpec {
+    name: "Overclock Terminal",
+    choices: &OVERCLOCK_TERMINAL_CHOICES,
+};
+
+pub const EVENT_TABLE: [&EventSpec; 3] = [&SCRAP_CACHE, &BLOOD_ALTAR, &OVERCLOCK_TERMINAL];
+
+pub const REST_CHOICES: [NodeChoiceSpec; 2] = [
+    NodeChoiceSpec {
+        id: "rest",
+        label: "Rest",
+        gold_cost: 0,
+        effects: &[NodeEffect::HealRatio(0.30)],
+    },
+    NodeChoiceSpec {
+        id: "train",
+        label: "Train",
+        gold_cost: 0,
+        effects: &[NodeEffect::AddAttack(2)],
+    },
+];
+
+pub const SHOP_STOCK: [NodeChoiceSpec; 4] = [
+    NodeChoiceSpec {
+        id: "repair_kit",
+        label: "Repair Kit",
+        gold_cost: 20,
+        effects: &[NodeEffect::HealRatio(0.35)],
+    },
+    NodeChoiceSpec {
+        id: "plating",
+        label: "Reinforced Plating",
+        gold_cost: 35,
+        effects: &[NodeEffect::AddMaxHp(15.0)],
+    },
+    NodeChoiceSpec {
+        id: "whetstone",
+        label: "Whetstone",
+        gold_cost: 40,
+        effects: &[NodeEffect::AddAttack(2)],
+    },
+    NodeChoiceSpec {
+        id: "servo_oil",
+        label: "Servo Oil",
+        gold_cost: 30,
+        effects: &[NodeEffect::AddSpeed(3.0)],
+    },
+];
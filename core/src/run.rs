@@ -1,89 +1,82 @@
-use crate::battle::{create_battle, player_hp_after_battle, run_battle};
+use crate::battle::{create_battle, run_battle, store_party_hp};
+use crate::enemy_spec::{encounter_enemies, pick_encounter};
+use crate::engine::node_system::{apply_node_choice, gain_gold, NodeOffer};
 use crate::event::Event;
 use crate::log::push_event;
+use crate::map::RunMap;
 use crate::model::{BattleOutcome, NodeType, RunState};
+use crate::node_spec::{BATTLE_GOLD_REWARD, BOSS_GOLD_REWARD};
 
-fn hp2(v: f32) -> f32 {
-    (v * 100.0).round() / 100.0
-}
-
-/// Runs one full run skeleton: normal battle nodes + final boss node.
+/// Runs one full run skeleton along a random path of the generated map.
+/// Event/Shop/Rest nodes resolve with their default choice.
 pub fn run_run_internal(seed: u64, max_nodes: u32) -> Vec<String> {
     let mut run = RunState::new(seed);
     let mut logs = Vec::new();
 
-    let planned_nodes: [NodeType; 6] = [
-        NodeType::Battle,
-        NodeType::Battle,
-        NodeType::Battle,
-        NodeType::Battle,
-        NodeType::Battle,
-        NodeType::Boss,
-    ];
-
-    let node_limit = max_nodes.min(planned_nodes.len() as u32) as usize;
+    let map = RunMap::for_seed(seed);
+    let node_limit = max_nodes.min(map.floor_count());
 
     push_event(&mut logs, Event::RunStart { seed: run.seed });
 
     let mut final_node_index = 0_u32;
+    let mut options = map.start_ids();
 
-    for (i, node_type) in planned_nodes.iter().take(node_limit).enumerate() {
-        run.stage = (i as u32) + 1;
-        final_node_index = run.stage;
-        let node_type_label = match node_type {
-            NodeType::Battle => "Battle",
-            NodeType::Boss => "Boss",
-            NodeType::Event => "Event",
-            NodeType::Shop => "Shop",
-            NodeType::Rest => "Rest",
+    while run.stage < node_limit && !options.is_empty() {
+        let node_id = options[run.rng.range_usize(options.len())];
+        let Some(node) = map.node(node_id) else {
+            break;
         };
+        let node_type = node.node_type;
+        options = node.next.clone();
+        run.floor = node.floor;
+        run.stage += 1;
+        final_node_index = run.stage;
         push_event(
             &mut logs,
             Event::NodeStart {
                 node_index: run.stage,
-                node_type: node_type_label,
+                node_type: node_type.as_str(),
+                floor: run.floor,
             },
         );
 
-        let mut battle = match node_type {
-            NodeType::Battle => create_battle(
-                run.player_hp,
-                run.player_max_hp,
-                run.player_atk,
-                run.player_speed,
-                1,
-                84.0,
-                11,
-                28.0,
-            ),
-            NodeType::Boss => create_battle(
-                run.player_hp,
-                run.player_max_hp,
-                run.player_atk,
-                run.player_speed,
-                1,
-                220.0,
-                14,
-                32.0,
-            ),
-            _ => continue,
-        };
-        let enemy_name = match node_type {
-            NodeType::Battle => "Rogue Drone",
-            NodeType::Boss => "Overstack Core",
-            _ => "Unknown",
-        };
+        if let Some(offer) = NodeOffer::roll(node_type, &mut run.rng) {
+            push_event(
+                &mut logs,
+                Event::NodeOffered {
+                    title: offer.title,
+                    choice_count: offer.choices.len() as u32,
+                },
+            );
+            let choice = offer.choices[offer.default_choice_index()];
+            apply_node_choice(&mut run, choice, &mut logs)
+                .expect("default node choices never cost gold");
+            continue;
+        }
 
-        match run_battle(&mut battle, &mut run.rng, run.stage, enemy_name, &mut logs) {
+        let encounter = pick_encounter(node_type, run.floor, &mut run.rng);
+        let mut battle = create_battle(&run.party, &encounter_enemies(encounter));
+
+        match run_battle(
+            &mut battle,
+            &mut run.rng,
+            run.stage,
+            encounter.name,
+            &mut logs,
+        ) {
             BattleOutcome::Victory => {
-                run.player_hp = hp2(player_hp_after_battle(&battle));
+                store_party_hp(&mut run, &battle);
 
                 // Temporary sustain rule for skeleton pacing.
-                let recover = hp2(run.player_max_hp * 0.20);
-                run.player_hp = hp2((run.player_hp + recover).min(run.player_max_hp));
+                run.recover_party(0.20);
+                let gold_reward = if node_type == NodeType::Boss {
+                    BOSS_GOLD_REWARD
+                } else {
+                    BATTLE_GOLD_REWARD
+                };
+                gain_gold(&mut run, gold_reward, &mut logs);
             }
             BattleOutcome::Defeat => {
-                run.player_hp = 0.0;
                 push_event(
                     &mut logs,
                     Event::RunEnd {
@@ -96,7 +89,6 @@ pub fn run_run_internal(seed: u64, max_nodes: u32) -> Vec<String> {
         }
     }
 
-    run.meta_placeholder = final_node_index;
     push_event(
         &mut logs,
         Event::RunEnd {
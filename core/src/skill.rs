@@ -1,5 +1,14 @@
+use crate::status_spec::{status_spec, StatusCategory};
+
 pub type SkillId = &'static str;
 
+/// Energy cap for every unit; energy does not carry over between battles.
+pub const MAX_ENERGY: u32 = 5;
+pub const STARTING_ENERGY: u32 = 2;
+/// Energy regained at the end of each of the unit's own turns.
+pub const ENERGY_PER_TURN: u32 = 1;
+
+/// Key into the `status_spec` table, which defines what each status does.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum StatusType {
     Burn,
@@ -10,19 +19,160 @@ pub enum StatusType {
     Stun,
     Might,
     Haste,
+    Regen,
 }
 
 impl StatusType {
     pub fn as_str(self) -> &'static str {
+        status_spec(self).name
+    }
+}
+
+/// Element of a hit, read from skill tags; decides which defenses apply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Ice,
+    Lightning,
+    /// Ignores armor and resistances.
+    Pure,
+}
+
+impl DamageType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DamageType::Physical => "physical",
+            DamageType::Fire => "fire",
+            DamageType::Ice => "ice",
+            DamageType::Lightning => "lightning",
+            DamageType::Pure => "pure",
+        }
+    }
+
+    /// First elemental tag wins; untagged skills hit as physical.
+    pub fn from_tags(tags: &[&str]) -> DamageType {
+        tags.iter()
+            .find_map(|tag| match *tag {
+                "fire" => Some(DamageType::Fire),
+                "ice" => Some(DamageType::Ice),
+                "lightning" => Some(DamageType::Lightning),
+                "pure" => Some(DamageType::Pure),
+                _ => None,
+            })
+            .unwrap_or(DamageType::Physical)
+    }
+}
+
+/// Selects statuses for cleanse and immunity effects.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusFilter {
+    Type(StatusType),
+    Category(StatusCategory),
+}
+
+impl StatusFilter {
+    pub fn matches(self, status_type: StatusType) -> bool {
+        match self {
+            StatusFilter::Type(expected) => status_type == expected,
+            StatusFilter::Category(category) => status_spec(status_type).category == category,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusFilter::Type(status_type) => status_type.as_str(),
+            StatusFilter::Category(category) => category.as_str(),
+        }
+    }
+}
+
+/// Additive adjustment to one stat: effective = (base + flat) * (1 + percent).
+#[derive(Clone, Copy, Debug)]
+pub struct StatModifier {
+    pub stat: StatType,
+    pub flat: f32,
+    pub percent: f32,
+}
+
+impl StatModifier {
+    pub fn scaled(self, factor: f32) -> StatModifier {
+        StatModifier {
+            flat: self.flat * factor,
+            percent: self.percent * factor,
+            ..self
+        }
+    }
+}
+
+/// Stages of the damage pipeline, in the order they run.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DamageStage {
+    Base,
+    /// Flat bonuses.
+    Additive,
+    Multiplicative,
+    /// Runs only for critical hits, after the attacker's `CritDamage`.
+    Crit,
+    /// Runs after the target's armor and resistances.
+    Mitigation,
+    /// Part of the hit soaked by the target's barrier.
+    Barrier,
+    /// Damage that reaches HP.
+    Final,
+}
+
+impl DamageStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DamageStage::Base => "base",
+            DamageStage::Additive => "additive",
+            DamageStage::Multiplicative => "multiplicative",
+            DamageStage::Crit => "crit",
+            DamageStage::Mitigation => "mitigation",
+            DamageStage::Barrier => "barrier",
+            DamageStage::Final => "final",
+        }
+    }
+}
+
+/// Which hits a `DamageModifier` affects, seen from the unit holding it.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageSide {
+    Dealt,
+    Taken,
+}
+
+/// Modifier a trait or status registers into one damage stage. `Additive` adds `value`,
+/// `Multiplicative`, `Crit` and `Mitigation` scale by `1 + value`; the other stages are
+/// computed by the pipeline and ignore modifiers. `condition` sees the attacker as source
+/// and the target as destination.
+#[derive(Clone, Copy, Debug)]
+pub struct DamageModifier {
+    pub stage: DamageStage,
+    pub side: DamageSide,
+    pub condition: Condition,
+    pub value: f32,
+}
+
+/// Integer comparison used by count-based conditions.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum Comparison {
+    AtLeast(u32),
+    AtMost(u32),
+    Exactly(u32),
+}
+
+impl Comparison {
+    pub fn matches(self, value: u32) -> bool {
         match self {
-            StatusType::Burn => "Burn",
-            StatusType::Freeze => "Freeze",
-            StatusType::Shock => "Shock",
-            StatusType::Break => "Break",
-            StatusType::Bleed => "Bleed",
-            StatusType::Stun => "Stun",
-            StatusType::Might => "Might",
-            StatusType::Haste => "Haste",
+            Comparison::AtLeast(n) => value >= n,
+            Comparison::AtMost(n) => value <= n,
+            Comparison::Exactly(n) => value == n,
         }
     }
 }
@@ -33,19 +183,97 @@ pub enum Condition {
     Always,
     SrcIsPlayer,
     DstIsEnemy,
+    /// Source of the trigger is the unit that owns the evaluated trait.
+    SrcIsOwner,
+    /// Target of the trigger is the unit that owns the evaluated trait.
+    DstIsOwner,
     AppliedStatusIs(StatusType),
     RandomRollBelow(f32),
     TargetHPBelow(f32),
+    TargetHPAbove(f32),
     TargetHasStatus(StatusType),
     TargetStatusCountAtLeast(u32),
+    TargetStacks(StatusType, Comparison),
+    SrcHPBelow(f32),
+    SrcHPAbove(f32),
+    SrcHasStatus(StatusType),
+    SrcStacks(StatusType, Comparison),
+    /// Turns the source has started this battle, the current one included.
+    TurnNumber(Comparison),
     All(&'static [Condition]),
+    Any(&'static [Condition]),
+    Not(&'static Condition),
+    /// The skill being resolved has this id.
+    SkillIs(SkillId),
+    SkillHasTag(&'static str),
+    DamageTypeIs(DamageType),
+    /// `TriggerContext::amount` (damage or heal) is at least this much.
+    DamageAtLeast(f32),
+    IsCrit,
+    /// `TriggerContext::stacks` of the applied, ticking or expiring status.
+    StacksAtLeast(u32),
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StatType {
     Attack,
     Speed,
+    /// Fraction of hit damage returned to the attacker as healing; base 0.
+    Lifesteal,
+    CritChance,
+    /// Damage multiplier of a critical hit.
+    CritDamage,
+    /// Hit chance before the target's evasion is subtracted.
+    Accuracy,
+    Evasion,
+}
+
+pub const BASE_CRIT_CHANCE: f32 = 0.05;
+pub const BASE_CRIT_DAMAGE: f32 = 1.5;
+pub const BASE_ACCURACY: f32 = 1.0;
+
+impl StatType {
+    /// Base value of the stats every unit shares; attack, speed and enemy evasion
+    /// come from hero and bestiary data instead.
+    pub fn shared_base(self) -> f32 {
+        match self {
+            StatType::CritChance => BASE_CRIT_CHANCE,
+            StatType::CritDamage => BASE_CRIT_DAMAGE,
+            StatType::Accuracy => BASE_ACCURACY,
+            StatType::Attack | StatType::Speed | StatType::Lifesteal | StatType::Evasion => 0.0,
+        }
+    }
+}
+
+/// Status granted by `EffectSpec::SelfBuff` for `stat`; stats without one cannot be self-buffed.
+pub fn buff_status_for(stat: StatType) -> Option<StatusType> {
+    match stat {
+        StatType::Attack => Some(StatusType::Might),
+        StatType::Speed => Some(StatusType::Haste),
+        _ => None,
+    }
+}
+
+/// How much an `EffectSpec::Heal` restores.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum HealAmount {
+    Flat(f32),
+    /// Fraction of the healed unit's max HP.
+    MaxHpPercent(f32),
+    /// Fraction of the damage dealt by the skill, or by the hit that fired the trigger.
+    DamagePercent(f32),
+}
+
+impl HealAmount {
+    pub fn scaled(self, factor: f32) -> HealAmount {
+        match self {
+            HealAmount::Flat(v) => HealAmount::Flat(v * factor),
+            HealAmount::MaxHpPercent(v) => HealAmount::MaxHpPercent(v * factor),
+            HealAmount::DamagePercent(v) => HealAmount::DamagePercent(v * factor),
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -53,7 +281,10 @@ pub enum StatType {
 pub enum EffectTarget {
     Src,
     Dst,
+    /// The hero involved in the trigger (src, dst or owner), else the first living hero.
     Player,
+    /// Living unit on the source's side with the lowest HP ratio.
+    Ally,
     Enemy,
 }
 
@@ -71,6 +302,8 @@ pub enum EffectSpec {
         stacks: u32,
         power: f32,
     },
+    /// Scales damage by `amp` while `condition` holds: the skill's later hits, or for a
+    /// trait the hit that fires its `OnDamageDealt` / `OnDamageTaken` rule.
     ConditionalDamageAmp {
         condition: Condition,
         amp: f32,
@@ -107,6 +340,146 @@ pub enum EffectSpec {
         target: EffectTarget,
         amount: f32,
     },
+    /// Removes every cleansable status matching `filter`.
+    Cleanse {
+        target: EffectTarget,
+        filter: StatusFilter,
+    },
+    /// Strips all stacks of `status_type` for `damage_per_stack` pure damage each.
+    ConsumeStacks {
+        target: EffectTarget,
+        status_type: StatusType,
+        damage_per_stack: f32,
+    },
+    /// Blocks new statuses matching `filter` for `duration` seconds.
+    GrantImmunity {
+        target: EffectTarget,
+        filter: StatusFilter,
+        duration: f32,
+    },
+    Heal {
+        target: EffectTarget,
+        amount: HealAmount,
+    },
+    /// Adds `amount` to the target's barrier, which then drains `decay` per second (0 keeps it).
+    GrantBarrier {
+        target: EffectTarget,
+        amount: f32,
+        decay: f32,
+    },
+}
+
+impl EffectSpec {
+    /// Copy with its magnitude multiplied by `factor`: damage, heal, barrier, buff and bonus
+    /// amounts, status power, proc chance (capped at 1) and added stacks (rounded), and the
+    /// part of a multiplier (power `mul`, damage `amp`) above 1. Durations, decay and
+    /// conditions are unchanged.
+    pub fn scaled(self, factor: f32) -> EffectSpec {
+        match self {
+            EffectSpec::DealDamage { multiplier, flat } => EffectSpec::DealDamage {
+                multiplier: multiplier * factor,
+                flat: flat * factor,
+            },
+            EffectSpec::ApplyStatus {
+                status_type,
+                base_chance,
+                duration,
+                stacks,
+                power,
+            } => EffectSpec::ApplyStatus {
+                status_type,
+                base_chance: (base_chance * factor).min(1.0),
+                duration,
+                stacks,
+                power: power * factor,
+            },
+            EffectSpec::ConditionalDamageAmp { condition, amp } => {
+                EffectSpec::ConditionalDamageAmp {
+                    condition,
+                    amp: 1.0 + (amp - 1.0) * factor,
+                }
+            }
+            EffectSpec::ConditionalApplyStatus {
+                condition,
+                status_type,
+                base_chance,
+                duration,
+                stacks,
+                power,
+            } => EffectSpec::ConditionalApplyStatus {
+                condition,
+                status_type,
+                base_chance: (base_chance * factor).min(1.0),
+                duration,
+                stacks,
+                power: power * factor,
+            },
+            EffectSpec::SelfBuff {
+                stat,
+                amount,
+                duration,
+            } => EffectSpec::SelfBuff {
+                stat,
+                amount: amount * factor,
+                duration,
+            },
+            EffectSpec::AddProcBonus { amount } => EffectSpec::AddProcBonus {
+                amount: amount * factor,
+            },
+            EffectSpec::AddResBonus { amount } => EffectSpec::AddResBonus {
+                amount: amount * factor,
+            },
+            EffectSpec::ModifyStatusPower { status_type, mul } => EffectSpec::ModifyStatusPower {
+                status_type,
+                mul: 1.0 + (mul - 1.0) * factor,
+            },
+            EffectSpec::AddStatusStacks {
+                target,
+                status_type,
+                stacks,
+            } => EffectSpec::AddStatusStacks {
+                target,
+                status_type,
+                stacks: (stacks as f32 * factor).round() as u32,
+            },
+            EffectSpec::DealPureDamage { target, amount } => EffectSpec::DealPureDamage {
+                target,
+                amount: amount * factor,
+            },
+            EffectSpec::ConsumeStacks {
+                target,
+                status_type,
+                damage_per_stack,
+            } => EffectSpec::ConsumeStacks {
+                target,
+                status_type,
+                damage_per_stack: damage_per_stack * factor,
+            },
+            EffectSpec::Heal { target, amount } => EffectSpec::Heal {
+                target,
+                amount: amount.scaled(factor),
+            },
+            EffectSpec::GrantBarrier {
+                target,
+                amount,
+                decay,
+            } => EffectSpec::GrantBarrier {
+                target,
+                amount: amount * factor,
+                decay,
+            },
+            EffectSpec::Cleanse { .. } | EffectSpec::GrantImmunity { .. } => self,
+        }
+    }
+}
+
+/// Which units a skill may be aimed at, relative to the caster.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkillTargeting {
+    Enemy,
+    Ally,
+    Caster,
 }
 
 #[allow(dead_code)]
@@ -114,10 +487,14 @@ pub enum EffectSpec {
 pub struct SkillSpec {
     pub id: SkillId,
     pub name: &'static str,
+    pub targeting: SkillTargeting,
     pub base_damage_multiplier: f32,
     pub flat_bonus_damage: Option<f32>,
     pub effects: &'static [EffectSpec],
     pub tags: &'static [&'static str],
+    /// Own turns the skill stays unavailable after use.
+    pub cooldown: u32,
+    pub energy_cost: u32,
 }
 
 const BASIC_ATTACK_EFFECTS: [EffectSpec; 1] = [EffectSpec::DealDamage {
@@ -167,6 +544,44 @@ const ARC_JOLT_EFFECTS: [EffectSpec; 2] = [
     },
 ];
 
+const DETONATE_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 0.6,
+        flat: 0.0,
+    },
+    EffectSpec::ConsumeStacks {
+        target: EffectTarget::Dst,
+        status_type: StatusType::Burn,
+        damage_per_stack: 4.0,
+    },
+];
+
+const PURIFY_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::Cleanse {
+        target: EffectTarget::Dst,
+        filter: StatusFilter::Category(StatusCategory::Debuff),
+    },
+    EffectSpec::GrantImmunity {
+        target: EffectTarget::Dst,
+        filter: StatusFilter::Category(StatusCategory::Debuff),
+        duration: 3.0,
+    },
+];
+
+const MEND_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::Heal {
+        target: EffectTarget::Dst,
+        amount: HealAmount::MaxHpPercent(0.15),
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Regen,
+        base_chance: 1.0,
+        duration: 4.0,
+        stacks: 1,
+        power: 2.0,
+    },
+];
+
 const RUIN_STRIKE_EFFECTS: [EffectSpec; 2] = [
     EffectSpec::DealDamage {
         multiplier: 1.1,
@@ -181,57 +596,273 @@ const RUIN_STRIKE_EFFECTS: [EffectSpec; 2] = [
     },
 ];
 
+const ICE_CARAPACE_EFFECTS: [EffectSpec; 1] = [EffectSpec::GrantBarrier {
+    target: EffectTarget::Src,
+    amount: 30.0,
+    decay: 2.0,
+}];
+
+const SCORCH_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 0.8,
+        flat: 0.0,
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Burn,
+        base_chance: 0.60,
+        duration: 4.0,
+        stacks: 1,
+        power: 1.0,
+    },
+];
+
+const FROST_SPIT_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 0.7,
+        flat: 0.0,
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Freeze,
+        base_chance: 0.55,
+        duration: 3.0,
+        stacks: 1,
+        power: 1.0,
+    },
+];
+
+const STATIC_BITE_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 0.9,
+        flat: 0.0,
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Shock,
+        base_chance: 0.45,
+        duration: 4.0,
+        stacks: 1,
+        power: 1.0,
+    },
+];
+
+const REND_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 0.9,
+        flat: 0.0,
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Bleed,
+        base_chance: 0.50,
+        duration: 5.0,
+        stacks: 1,
+        power: 1.0,
+    },
+];
+
+const CRUSHING_SLAM_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::DealDamage {
+        multiplier: 1.3,
+        flat: 0.0,
+    },
+    EffectSpec::ApplyStatus {
+        status_type: StatusType::Stun,
+        base_chance: 0.50,
+        duration: 1.0,
+        stacks: 1,
+        power: 1.0,
+    },
+];
+
+const CORE_OVERLOAD_EFFECTS: [EffectSpec; 2] = [
+    EffectSpec::SelfBuff {
+        stat: StatType::Attack,
+        amount: 2.0,
+        duration: 6.0,
+    },
+    EffectSpec::DealDamage {
+        multiplier: 1.5,
+        flat: 0.0,
+    },
+];
+
 pub const BASIC_ATTACK: SkillSpec = SkillSpec {
     id: "basic_attack",
     name: "Basic Attack",
+    targeting: SkillTargeting::Enemy,
     base_damage_multiplier: 1.0,
     flat_bonus_damage: None,
     effects: &BASIC_ATTACK_EFFECTS,
     tags: &["basic", "physical"],
+    cooldown: 0,
+    energy_cost: 0,
 };
 
 pub const EMBER_LASH: SkillSpec = SkillSpec {
     id: "ember_lash",
     name: "Ember Lash",
+    targeting: SkillTargeting::Enemy,
     base_damage_multiplier: 1.0,
     flat_bonus_damage: None,
     effects: &EMBER_LASH_EFFECTS,
     tags: &["skill", "fire"],
+    cooldown: 0,
+    energy_cost: 1,
 };
 
 pub const FROST_BITE: SkillSpec = SkillSpec {
     id: "frost_bite",
     name: "Frost Bite",
+    targeting: SkillTargeting::Enemy,
     base_damage_multiplier: 1.0,
     flat_bonus_damage: None,
     effects: &FROST_BITE_EFFECTS,
     tags: &["skill", "ice"],
+    cooldown: 1,
+    energy_cost: 1,
 };
 
 pub const ARC_JOLT: SkillSpec = SkillSpec {
     id: "arc_jolt",
     name: "Arc Jolt",
+    targeting: SkillTargeting::Enemy,
     base_damage_multiplier: 1.0,
     flat_bonus_damage: None,
     effects: &ARC_JOLT_EFFECTS,
     tags: &["skill", "lightning"],
+    cooldown: 0,
+    energy_cost: 1,
 };
 
 pub const RUIN_STRIKE: SkillSpec = SkillSpec {
     id: "ruin_strike",
     name: "Ruin Strike",
+    targeting: SkillTargeting::Enemy,
     base_damage_multiplier: 1.0,
     flat_bonus_damage: None,
     effects: &RUIN_STRIKE_EFFECTS,
-    tags: &["skill", "debuff"],
+    tags: &["skill", "physical", "debuff"],
+    cooldown: 2,
+    energy_cost: 3,
 };
 
-pub const PLAYER_SLOT_SKILL_IDS: [SkillId; 4] = [
-    EMBER_LASH.id,
-    FROST_BITE.id,
-    ARC_JOLT.id,
-    RUIN_STRIKE.id,
-];
+pub const DETONATE: SkillSpec = SkillSpec {
+    id: "detonate",
+    name: "Detonate",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &DETONATE_EFFECTS,
+    tags: &["skill", "fire"],
+    cooldown: 2,
+    energy_cost: 2,
+};
+
+pub const PURIFY: SkillSpec = SkillSpec {
+    id: "purify",
+    name: "Purify",
+    targeting: SkillTargeting::Caster,
+    base_damage_multiplier: 0.0,
+    flat_bonus_damage: None,
+    effects: &PURIFY_EFFECTS,
+    tags: &["skill", "support"],
+    cooldown: 3,
+    energy_cost: 1,
+};
+
+pub const MEND: SkillSpec = SkillSpec {
+    id: "mend",
+    name: "Mend",
+    targeting: SkillTargeting::Ally,
+    base_damage_multiplier: 0.0,
+    flat_bonus_damage: None,
+    effects: &MEND_EFFECTS,
+    tags: &["skill", "support", "heal"],
+    cooldown: 3,
+    energy_cost: 2,
+};
+
+pub const ICE_CARAPACE: SkillSpec = SkillSpec {
+    id: "ice_carapace",
+    name: "Ice Carapace",
+    targeting: SkillTargeting::Caster,
+    base_damage_multiplier: 0.0,
+    flat_bonus_damage: None,
+    effects: &ICE_CARAPACE_EFFECTS,
+    tags: &["enemy", "ice", "barrier"],
+    cooldown: 4,
+    energy_cost: 0,
+};
+
+pub const SCORCH: SkillSpec = SkillSpec {
+    id: "scorch",
+    name: "Scorch",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &SCORCH_EFFECTS,
+    tags: &["enemy", "fire"],
+    cooldown: 0,
+    energy_cost: 0,
+};
+
+pub const FROST_SPIT: SkillSpec = SkillSpec {
+    id: "frost_spit",
+    name: "Frost Spit",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &FROST_SPIT_EFFECTS,
+    tags: &["enemy", "ice"],
+    cooldown: 1,
+    energy_cost: 0,
+};
+
+pub const STATIC_BITE: SkillSpec = SkillSpec {
+    id: "static_bite",
+    name: "Static Bite",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &STATIC_BITE_EFFECTS,
+    tags: &["enemy", "lightning"],
+    cooldown: 0,
+    energy_cost: 0,
+};
+
+pub const REND: SkillSpec = SkillSpec {
+    id: "rend",
+    name: "Rend",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &REND_EFFECTS,
+    tags: &["enemy", "physical"],
+    cooldown: 0,
+    energy_cost: 0,
+};
+
+pub const CRUSHING_SLAM: SkillSpec = SkillSpec {
+    id: "crushing_slam",
+    name: "Crushing Slam",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &CRUSHING_SLAM_EFFECTS,
+    tags: &["enemy", "physical"],
+    cooldown: 2,
+    energy_cost: 0,
+};
+
+pub const CORE_OVERLOAD: SkillSpec = SkillSpec {
+    id: "core_overload",
+    name: "Core Overload",
+    targeting: SkillTargeting::Enemy,
+    base_damage_multiplier: 1.0,
+    flat_bonus_damage: None,
+    effects: &CORE_OVERLOAD_EFFECTS,
+    tags: &["enemy", "buff"],
+    cooldown: 3,
+    energy_cost: 0,
+};
 
 pub fn skill_by_id(id: SkillId) -> Option<&'static SkillSpec> {
     match id {
@@ -240,20 +871,33 @@ pub fn skill_by_id(id: SkillId) -> Option<&'static SkillSpec> {
         "frost_bite" => Some(&FROST_BITE),
         "arc_jolt" => Some(&ARC_JOLT),
         "ruin_strike" => Some(&RUIN_STRIKE),
+        "detonate" => Some(&DETONATE),
+        "purify" => Some(&PURIFY),
+        "mend" => Some(&MEND),
+        "ice_carapace" => Some(&ICE_CARAPACE),
+        "scorch" => Some(&SCORCH),
+        "frost_spit" => Some(&FROST_SPIT),
+        "static_bite" => Some(&STATIC_BITE),
+        "rend" => Some(&REND),
+        "crushing_slam" => Some(&CRUSHING_SLAM),
+        "core_overload" => Some(&CORE_OVERLOAD),
         _ => None,
     }
 }
 
-pub fn player_skill_for_slot(slot: u32) -> &'static SkillSpec {
-    let idx = (slot as usize).min(PLAYER_SLOT_SKILL_IDS.len() - 1);
-    let id = PLAYER_SLOT_SKILL_IDS[idx];
+/// Skill bound to action `slot` of a loadout; out-of-range slots clamp to the last one.
+pub fn skill_for_slot(skills: &[SkillId], slot: u32) -> &'static SkillSpec {
+    let Some(last) = skills.len().checked_sub(1) else {
+        return &BASIC_ATTACK;
+    };
+    let id = skills[(slot as usize).min(last)];
     skill_by_id(id).unwrap_or(&BASIC_ATTACK)
 }
 
-pub fn player_skill_names() -> Vec<String> {
-    PLAYER_SLOT_SKILL_IDS
+pub fn skill_names(skills: &[SkillId]) -> Vec<String> {
+    skills
         .iter()
-        .filter_map(|id| skill_by_id(*id))
+        .filter_map(|id| skill_by_id(id))
         .map(|spec| spec.name.to_string())
         .collect()
 }
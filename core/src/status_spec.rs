@@ -0,0 +1,329 @@
+use crate::skill::{
+    Condition, DamageModifier, DamageSide, DamageStage, DamageType, StatModifier, StatType,
+    StatusType,
+};
+
+/// Damage a status deals each time its tick meter fills.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TickFormula {
+    None,
+    /// `power * stacks` per tick.
+    PowerPerStack,
+    /// `power` per tick regardless of stacks.
+    FlatPower,
+}
+
+/// How a new application merges with the statuses already on the unit.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StackingPolicy {
+    /// Stacks add up to `max_stacks`.
+    Add,
+    /// Stacks stay as they are; only duration and power are renewed.
+    Refresh,
+    /// Keeps the larger of the current and incoming stacks.
+    Max,
+    /// Every application is its own instance; `max_stacks` caps the instance count.
+    Independent,
+}
+
+/// Broad grouping used by cleanse and immunity effects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusCategory {
+    Buff,
+    Debuff,
+}
+
+impl StatusCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusCategory::Buff => "buff",
+            StatusCategory::Debuff => "debuff",
+        }
+    }
+}
+
+/// What a re-application does to the remaining duration of a merged status.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DurationPolicy {
+    /// Restarts at the incoming duration.
+    Refresh,
+    /// Adds the incoming duration, up to `max_duration`.
+    Extend,
+    /// Keeps whichever is longer.
+    Longest,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct StatusSpec {
+    pub status_type: StatusType,
+    pub name: &'static str,
+    pub category: StatusCategory,
+    pub tick: TickFormula,
+    pub stacking: StackingPolicy,
+    pub max_stacks: u32,
+    pub duration: DurationPolicy,
+    /// Cap for `DurationPolicy::Extend`.
+    pub max_duration: f32,
+    /// On expiry only one stack falls off and the duration restarts.
+    pub decays: bool,
+    /// Applied once per stack.
+    pub stat_modifiers: &'static [StatModifier],
+    /// Damage pipeline modifiers of the holder, applied once per stack.
+    pub damage_modifiers: &'static [DamageModifier],
+    /// Gauge fill multiplier while the status is active.
+    pub gauge_mult: f32,
+    /// A unit due to act loses that action and the status is consumed.
+    pub skips_turn: bool,
+    /// Whether cleanse effects may remove it.
+    pub cleansable: bool,
+    /// Ticks restore HP to the holder instead of dealing damage.
+    pub heals: bool,
+    /// Damage type of its ticks in the damage pipeline.
+    pub tick_damage_type: DamageType,
+}
+
+const MIGHT_MODIFIERS: [StatModifier; 1] = [StatModifier {
+    stat: StatType::Attack,
+    flat: 0.0,
+    percent: 0.10,
+}];
+
+const HASTE_MODIFIERS: [StatModifier; 1] = [StatModifier {
+    stat: StatType::Speed,
+    flat: 0.0,
+    percent: 0.25,
+}];
+
+/// Hits against a Broken unit deal 10% more per stack.
+const BREAK_DAMAGE_MODIFIERS: [DamageModifier; 1] = [DamageModifier {
+    stage: DamageStage::Multiplicative,
+    side: DamageSide::Taken,
+    condition: Condition::Always,
+    value: 0.10,
+}];
+
+pub const BURN: StatusSpec = StatusSpec {
+    status_type: StatusType::Burn,
+    name: "Burn",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::PowerPerStack,
+    stacking: StackingPolicy::Add,
+    max_stacks: 10,
+    duration: DurationPolicy::Refresh,
+    max_duration: 10.0,
+    decays: true,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Fire,
+};
+
+pub const FREEZE: StatusSpec = StatusSpec {
+    status_type: StatusType::Freeze,
+    name: "Freeze",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::None,
+    stacking: StackingPolicy::Refresh,
+    max_stacks: 1,
+    duration: DurationPolicy::Refresh,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 0.5,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub const SHOCK: StatusSpec = StatusSpec {
+    status_type: StatusType::Shock,
+    name: "Shock",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::PowerPerStack,
+    stacking: StackingPolicy::Add,
+    max_stacks: 10,
+    duration: DurationPolicy::Longest,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Lightning,
+};
+
+pub const BREAK: StatusSpec = StatusSpec {
+    status_type: StatusType::Break,
+    name: "Break",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::None,
+    stacking: StackingPolicy::Add,
+    max_stacks: 5,
+    duration: DurationPolicy::Refresh,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &BREAK_DAMAGE_MODIFIERS,
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: false,
+    heals: false,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub const BLEED: StatusSpec = StatusSpec {
+    status_type: StatusType::Bleed,
+    name: "Bleed",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::PowerPerStack,
+    stacking: StackingPolicy::Independent,
+    max_stacks: 5,
+    duration: DurationPolicy::Longest,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Physical,
+};
+
+pub const STUN: StatusSpec = StatusSpec {
+    status_type: StatusType::Stun,
+    name: "Stun",
+    category: StatusCategory::Debuff,
+    tick: TickFormula::None,
+    stacking: StackingPolicy::Refresh,
+    max_stacks: 1,
+    duration: DurationPolicy::Longest,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 0.0,
+    skips_turn: true,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub const MIGHT: StatusSpec = StatusSpec {
+    status_type: StatusType::Might,
+    name: "Might",
+    category: StatusCategory::Buff,
+    tick: TickFormula::None,
+    stacking: StackingPolicy::Add,
+    max_stacks: 5,
+    duration: DurationPolicy::Refresh,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &MIGHT_MODIFIERS,
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub const HASTE: StatusSpec = StatusSpec {
+    status_type: StatusType::Haste,
+    name: "Haste",
+    category: StatusCategory::Buff,
+    tick: TickFormula::None,
+    stacking: StackingPolicy::Add,
+    max_stacks: 3,
+    duration: DurationPolicy::Extend,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &HASTE_MODIFIERS,
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: false,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub const REGEN: StatusSpec = StatusSpec {
+    status_type: StatusType::Regen,
+    name: "Regen",
+    category: StatusCategory::Buff,
+    tick: TickFormula::PowerPerStack,
+    stacking: StackingPolicy::Add,
+    max_stacks: 5,
+    duration: DurationPolicy::Refresh,
+    max_duration: 10.0,
+    decays: false,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    gauge_mult: 1.0,
+    skips_turn: false,
+    cleansable: true,
+    heals: true,
+    tick_damage_type: DamageType::Pure,
+};
+
+pub fn status_spec(status_type: StatusType) -> &'static StatusSpec {
+    match status_type {
+        StatusType::Burn => &BURN,
+        StatusType::Freeze => &FREEZE,
+        StatusType::Shock => &SHOCK,
+        StatusType::Break => &BREAK,
+        StatusType::Bleed => &BLEED,
+        StatusType::Stun => &STUN,
+        StatusType::Might => &MIGHT,
+        StatusType::Haste => &HASTE,
+        StatusType::Regen => &REGEN,
+    }
+}
+
+impl StatusSpec {
+    /// Damage of one tick at `power` and `stacks`; zero for statuses that do not tick.
+    pub fn tick_amount(&self, power: f32, stacks: u32) -> f32 {
+        match self.tick {
+            TickFormula::None => 0.0,
+            TickFormula::PowerPerStack => (power * stacks as f32).max(0.01),
+            TickFormula::FlatPower => power.max(0.01),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::status_spec;
+    use crate::skill::StatusType;
+
+    #[test]
+    fn every_status_key_maps_to_its_own_spec() {
+        let keys = [
+            StatusType::Burn,
+            StatusType::Freeze,
+            StatusType::Shock,
+            StatusType::Break,
+            StatusType::Bleed,
+            StatusType::Stun,
+            StatusType::Might,
+            StatusType::Haste,
+            StatusType::Regen,
+        ];
+        for key in keys {
+            let spec = status_spec(key);
+            assert_eq!(spec.status_type, key);
+            assert!(spec.max_stacks >= 1, "{} needs a stack cap", spec.name);
+        }
+    }
+}
@@ -3,12 +3,19 @@ use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 use crate::battle::create_battle;
+use crate::enemy_spec::{encounter_enemies, enemy_by_id, pick_encounter};
+use crate::engine::node_system::NodeOffer;
 use crate::event::Event;
+use crate::hero_spec::{hero_by_id, selectable_hero_ids, selectable_hero_names, MAX_PARTY_SIZE};
 use crate::log::push_event;
-use crate::model::{BattleState, NodeType, RunState};
-use crate::skill::{player_skill_names, StatusType};
+use crate::map::RunMap;
+use crate::model::{BattleState, HeroState, NodeType, RunState};
+use crate::skill::{
+    skill_names, DamageType, SkillId, SkillSpec, StatusFilter, StatusType, STARTING_ENERGY,
+};
 use crate::trait_spec::{
-    active_trait_names, selectable_trait_ids, selectable_trait_names, trait_by_id, TraitId, TriggerType,
+    selectable_trait_ids, selectable_trait_names, trait_by_id, TraitId, TraitSpec, TriggerType,
+    MAX_ACTIVE_TRAITS,
 };
 
 mod manager;
@@ -24,17 +31,51 @@ pub(crate) fn hp2(v: f32) -> f32 {
 #[derive(Clone)]
 pub(crate) struct ActiveStatus {
     pub(crate) status_type: StatusType,
+    /// Unit that applied this instance (the latest applier once merged); ticks are credited to it.
+    pub(crate) src_idx: usize,
     pub(crate) stacks: u32,
     pub(crate) duration: f32,
+    /// Duration of the application, restored when a decaying status loses a stack.
+    pub(crate) base_duration: f32,
     pub(crate) power: f32,
     pub(crate) tick_meter: f32,
 }
 
+/// Temporary protection against statuses matching `filter`.
+#[derive(Clone, Copy)]
+pub(crate) struct Immunity {
+    pub(crate) filter: StatusFilter,
+    pub(crate) remaining: f32,
+}
+
+/// Action an enemy commits to when its gauge starts filling.
+#[derive(Clone, Copy)]
+pub(crate) struct EnemyIntent {
+    pub(crate) skill: &'static SkillSpec,
+    pub(crate) target_idx: usize,
+}
+
 pub(crate) struct UnitRuntime {
     pub(crate) statuses: Vec<ActiveStatus>,
+    /// Unit-owned traits (enemy bestiary traits); run-level traits live on `ActiveRun`.
+    pub(crate) traits: Vec<TraitId>,
     pub(crate) proc_bonus: f32,
     pub(crate) res_bonus: f32,
     pub(crate) status_power_mult: HashMap<StatusType, f32>,
+    /// Next loadout index for `EnemyPolicy::Rotation`.
+    pub(crate) rotation_cursor: usize,
+    pub(crate) intent: Option<EnemyIntent>,
+    /// Last intent sent out as `IntentChanged`; outlives `intent` being taken for the turn.
+    pub(crate) announced_intent: Option<EnemyIntent>,
+    pub(crate) energy: u32,
+    /// Own turns left before a skill can be used again; absent means ready.
+    pub(crate) cooldowns: HashMap<SkillId, u32>,
+    pub(crate) immunities: Vec<Immunity>,
+    /// Absorbs hit damage before HP.
+    pub(crate) barrier: f32,
+    /// Barrier lost per second; set by the latest grant.
+    pub(crate) barrier_decay: f32,
+    pub(crate) turns_taken: u32,
 }
 
 pub(crate) struct ActiveBattle {
@@ -44,22 +85,54 @@ pub(crate) struct ActiveBattle {
 
 impl ActiveBattle {
     pub(crate) fn new(state: BattleState) -> Self {
-        let runtime = (0..state.units.len())
-            .map(|_| UnitRuntime {
+        let runtime = state
+            .units
+            .iter()
+            .map(|unit| UnitRuntime {
                 statuses: Vec::new(),
+                traits: unit
+                    .enemy_id
+                    .and_then(enemy_by_id)
+                    .map(|spec| spec.traits.to_vec())
+                    .unwrap_or_default(),
                 proc_bonus: 0.0,
                 res_bonus: 0.0,
                 status_power_mult: HashMap::new(),
+                rotation_cursor: 0,
+                intent: None,
+                announced_intent: None,
+                energy: STARTING_ENERGY,
+                cooldowns: HashMap::new(),
+                immunities: Vec::new(),
+                barrier: 0.0,
+                barrier_decay: 0.0,
+                turns_taken: 0,
             })
             .collect();
         Self { state, runtime }
     }
 }
 
+/// Run trait and its level; drafting an owned trait again raises the level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ActiveTrait {
+    pub(crate) id: TraitId,
+    pub(crate) level: u32,
+}
+
+impl ActiveTrait {
+    pub(crate) fn new(id: TraitId) -> Self {
+        Self { id, level: 1 }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub(crate) enum ActionKind {
     BasicAttack,
     SkillSlot(u32),
+    NodeChoice(u32),
+    PathChoice(u32),
+    TraitChoice(u32),
 }
 
 #[derive(Clone, Copy)]
@@ -68,22 +141,98 @@ pub(crate) struct TriggerContext {
     pub(crate) src_idx: Option<usize>,
     pub(crate) dst_idx: Option<usize>,
     pub(crate) applied_status: Option<StatusType>,
+    /// Unit owning the trait being evaluated; filled in per trait by `process_trait_triggers`.
+    pub(crate) owner_idx: Option<usize>,
+    /// Damage dealt by the triggering hit, or HP restored by the triggering heal.
+    pub(crate) amount: Option<f32>,
+    /// Skill being resolved when the trigger fired; filled in by `process_trait_triggers`.
+    pub(crate) skill: Option<&'static SkillSpec>,
+    pub(crate) damage_type: Option<DamageType>,
+    pub(crate) crit: bool,
+    /// Stacks of `applied_status` on the target once the status event resolved.
+    pub(crate) stacks: Option<u32>,
+}
+
+impl TriggerContext {
+    /// Context between two units with every optional detail unset.
+    pub(crate) fn new(
+        trigger_type: TriggerType,
+        src_idx: Option<usize>,
+        dst_idx: Option<usize>,
+    ) -> Self {
+        Self {
+            trigger_type,
+            src_idx,
+            dst_idx,
+            applied_status: None,
+            owner_idx: None,
+            amount: None,
+            skill: None,
+            damage_type: None,
+            crit: false,
+            stacks: None,
+        }
+    }
+
+    /// Unit a trait's self effects land on: the trait owner, else the trigger source.
+    pub(crate) fn self_idx(&self) -> Option<usize> {
+        self.owner_idx.or(self.src_idx)
+    }
 }
 
 #[wasm_bindgen]
 pub struct ActionInput {
     kind: u8,
     index: u32,
+    /// Battle unit id to act on; a random valid target is used when unset.
+    target: Option<u32>,
 }
 
 #[wasm_bindgen]
 impl ActionInput {
     pub fn basic_attack() -> ActionInput {
-        ActionInput { kind: 0, index: 0 }
+        ActionInput {
+            kind: 0,
+            index: 0,
+            target: None,
+        }
     }
 
     pub fn skill_slot(index: u32) -> ActionInput {
-        ActionInput { kind: 1, index }
+        ActionInput {
+            kind: 1,
+            index,
+            target: None,
+        }
+    }
+
+    pub fn node_choice(index: u32) -> ActionInput {
+        ActionInput {
+            kind: 2,
+            index,
+            target: None,
+        }
+    }
+
+    pub fn path_choice(node_id: u32) -> ActionInput {
+        ActionInput {
+            kind: 3,
+            index: node_id,
+            target: None,
+        }
+    }
+
+    pub fn trait_choice(index: u32) -> ActionInput {
+        ActionInput {
+            kind: 4,
+            index,
+            target: None,
+        }
+    }
+
+    pub fn with_target(mut self, unit_id: u32) -> ActionInput {
+        self.target = Some(unit_id);
+        self
     }
 }
 
@@ -91,6 +240,9 @@ impl ActionInput {
     fn to_kind(&self) -> ActionKind {
         match self.kind {
             1 => ActionKind::SkillSlot(self.index.min(3)),
+            2 => ActionKind::NodeChoice(self.index),
+            3 => ActionKind::PathChoice(self.index),
+            4 => ActionKind::TraitChoice(self.index),
             _ => ActionKind::BasicAttack,
         }
     }
@@ -100,6 +252,12 @@ impl ActionInput {
 pub struct StepResult {
     pub events: Vec<String>,
     pub need_input: bool,
+    /// `"action"` in battle, `"node_choice"` at Event/Shop/Rest nodes,
+    /// `"path_choice"` when the next map node must be picked, `"trait_choice"` during the
+    /// post-victory trait draft, empty otherwise.
+    pub input_kind: String,
+    /// Battle unit id of the hero waiting for an `"action"` input.
+    pub input_unit_id: Option<u32>,
     pub ended: bool,
     pub error: String,
 }
@@ -112,13 +270,65 @@ pub struct StatusSnapshot {
     pub duration: f32,
 }
 
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct ResistanceSnapshot {
+    pub damage_type: String,
+    /// Fraction ignored; negative means the unit is weak to it.
+    pub value: f32,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct IntentSnapshot {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub target: String,
+    pub target_id: u32,
+    pub estimated_damage: f32,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct SkillReadinessSnapshot {
+    pub skill_id: String,
+    pub name: String,
+    pub cooldown_remaining: u32,
+    pub energy_cost: u32,
+    /// Off cooldown and affordable with the unit's current energy.
+    pub ready: bool,
+}
+
 #[wasm_bindgen(getter_with_clone)]
 #[derive(Clone)]
 pub struct UnitSnapshot {
+    /// Stable battle unit id; use it as the action target.
+    pub unit_id: u32,
+    pub name: String,
     pub hp: f32,
     pub max_hp: f32,
+    pub base_atk: f32,
+    /// Attack after status and trait modifiers.
+    pub atk: f32,
+    pub base_speed: f32,
+    /// Speed after status and trait modifiers; Freeze/Stun gauge penalties are not included.
+    pub speed: f32,
+    pub crit_chance: f32,
+    pub crit_damage: f32,
+    pub accuracy: f32,
+    pub evasion: f32,
     pub action_gauge: f32,
+    /// Damage the unit can still absorb before losing HP.
+    pub barrier: f32,
+    pub armor: f32,
+    pub resistances: Vec<ResistanceSnapshot>,
     pub statuses: Vec<StatusSnapshot>,
+    /// Telegraphed next action; only enemies in battle have one.
+    pub intent: Option<IntentSnapshot>,
+    pub energy: u32,
+    pub max_energy: u32,
+    /// Skill slots for heroes, the loadout for enemies, in order.
+    pub skills: Vec<SkillReadinessSnapshot>,
 }
 
 #[wasm_bindgen(getter_with_clone)]
@@ -126,49 +336,83 @@ pub struct Snapshot {
     pub run_state: String,
     pub run_result: String,
     pub node_index: u32,
+    pub node_type: String,
+    pub floor: u32,
     pub battle_index: u32,
+    pub gold: u32,
     pub elapsed_time: f32,
-    pub player: UnitSnapshot,
-    pub enemy: UnitSnapshot,
+    /// Every hero in party order; unit ids match the battle unit ids.
+    pub party: Vec<UnitSnapshot>,
+    /// Every enemy of the current battle in unit order, including defeated ones.
+    pub enemies: Vec<UnitSnapshot>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct MapNodeSnapshot {
+    pub id: u32,
+    pub act: u32,
+    pub floor: u32,
+    pub lane: u32,
+    pub node_type: String,
+    pub next: Vec<u32>,
+    pub visited: bool,
+    pub current: bool,
+    /// True when the node can be picked as the next step of the path.
+    pub selectable: bool,
 }
 
 pub(crate) struct ActiveRun {
     pub(crate) seed: u64,
     pub(crate) max_nodes: u32,
     pub(crate) run: RunState,
-    pub(crate) planned_nodes: [NodeType; 6],
+    pub(crate) map: RunMap,
+    /// Map node ids visited so far; the last entry is the current node.
+    pub(crate) path: Vec<u32>,
+    pub(crate) awaiting_path: bool,
     pub(crate) node_index: u32,
     pub(crate) battle_index: u32,
     pub(crate) current_battle: Option<ActiveBattle>,
+    pub(crate) pending_node: Option<NodeOffer>,
     pub(crate) waiting_for_input: bool,
     pub(crate) ended: bool,
     pub(crate) result: &'static str,
     pub(crate) elapsed_time: f32,
-    pub(crate) active_traits: Vec<TraitId>,
+    pub(crate) active_traits: Vec<ActiveTrait>,
+    /// Traits offered by the post-victory draft; empty when no draft is open.
+    pub(crate) pending_traits: Vec<TraitId>,
+    /// Skill `execute_skill` is resolving; stamped onto every trigger it causes.
+    pub(crate) resolving_skill: Option<&'static SkillSpec>,
+    /// Specs the hero owns on top of `active_traits`, so tests can react with traits
+    /// the registry does not ship.
+    #[cfg(test)]
+    pub(crate) injected_traits: Vec<&'static TraitSpec>,
 }
 
 impl ActiveRun {
     pub(crate) fn new(seed: u64, max_nodes: u32) -> Self {
+        let run = RunState::new(seed);
+        let map = RunMap::for_seed(seed);
         Self {
             seed,
-            max_nodes: max_nodes.min(6),
-            run: RunState::new(seed),
-            planned_nodes: [
-                NodeType::Battle,
-                NodeType::Battle,
-                NodeType::Battle,
-                NodeType::Battle,
-                NodeType::Battle,
-                NodeType::Boss,
-            ],
+            max_nodes: max_nodes.min(map.floor_count()),
+            run,
+            map,
+            path: Vec::new(),
+            awaiting_path: false,
             node_index: 0,
             battle_index: 0,
             current_battle: None,
+            pending_node: None,
             waiting_for_input: false,
             ended: false,
             result: "none",
             elapsed_time: 0.0,
             active_traits: Vec::new(),
+            pending_traits: Vec::new(),
+            resolving_skill: None,
+            #[cfg(test)]
+            injected_traits: Vec::new(),
         }
     }
 
@@ -176,32 +420,86 @@ impl ActiveRun {
         *self = Self::new(self.seed, self.max_nodes);
     }
 
+    /// Owned trait names with their levels, e.g. `"Cinder Scholar Lv2"`.
     pub(crate) fn active_trait_names(&self) -> Vec<String> {
-        active_trait_names(&self.active_traits)
+        self.active_traits
+            .iter()
+            .filter_map(|owned| trait_by_id(owned.id).map(|t| (t, owned.level)))
+            .map(|(t, level)| format!("{} Lv{level}", t.name))
+            .collect()
+    }
+
+    pub(crate) fn player_skill_names(&self) -> Vec<String> {
+        self.waiting_hero_idx()
+            .and_then(|idx| self.hero_for_unit(idx))
+            .map(|hero| skill_names(hero.skills))
+            .unwrap_or_default()
     }
 
-    pub(crate) fn set_single_active_trait(&mut self, trait_id: &str) -> bool {
+    pub(crate) fn set_party(&mut self, hero_ids: &[String]) -> bool {
+        let started = self.node_index > 0 || self.current_battle.is_some();
+        if started || hero_ids.is_empty() || hero_ids.len() > MAX_PARTY_SIZE {
+            return false;
+        }
+        let mut party = Vec::new();
+        for id in hero_ids {
+            let Some(spec) = hero_by_id(id) else {
+                return false;
+            };
+            if party.iter().any(|h: &HeroState| h.hero_id == spec.id) {
+                return false;
+            }
+            party.push(HeroState::from_spec(spec));
+        }
+        self.run.party = party;
+        true
+    }
+
+    /// Adds `trait_id` at level 1, or upgrades it when already owned. Fails for unknown
+    /// traits, at the trait's max level, and for new traits once `MAX_ACTIVE_TRAITS` is hit.
+    pub(crate) fn add_active_trait(&mut self, trait_id: &str) -> bool {
         let Some(spec) = trait_by_id(trait_id) else {
             return false;
         };
-        self.active_traits.clear();
-        self.active_traits.push(spec.id);
-        true
+        self.gain_trait(spec).is_some()
     }
 
-    pub(crate) fn current_node_type(&self) -> Option<NodeType> {
-        if self.node_index == 0 {
+    /// Level of `spec` after adding or upgrading it; `None` when neither is possible.
+    pub(crate) fn gain_trait(&mut self, spec: &'static TraitSpec) -> Option<u32> {
+        if let Some(owned) = self.active_traits.iter_mut().find(|t| t.id == spec.id) {
+            if owned.level >= spec.max_level {
+                return None;
+            }
+            owned.level += 1;
+            return Some(owned.level);
+        }
+        if self.active_traits.len() >= MAX_ACTIVE_TRAITS {
             return None;
         }
-        self.planned_nodes.get((self.node_index - 1) as usize).copied()
+        self.active_traits.push(ActiveTrait::new(spec.id));
+        Some(1)
+    }
+
+    pub(crate) fn current_node_type(&self) -> Option<NodeType> {
+        let id = *self.path.last()?;
+        self.map.node(id).map(|n| n.node_type)
     }
 
-    pub(crate) fn ensure_battle_started(&mut self, events: &mut Vec<String>) {
-        if self.current_battle.is_some() || self.ended {
+    /// Advances along the map when no battle, trait draft, node choice or path choice is in
+    /// progress.
+    /// Pauses for a path choice when the current node branches.
+    pub(crate) fn ensure_node_started(&mut self, events: &mut Vec<String>) {
+        if self.current_battle.is_some()
+            || self.pending_node.is_some()
+            || !self.pending_traits.is_empty()
+            || self.awaiting_path
+            || self.ended
+        {
             return;
         }
 
-        if self.node_index >= self.max_nodes {
+        let options = self.next_node_options();
+        if self.node_index >= self.max_nodes || options.is_empty() {
             self.ended = true;
             self.result = "win";
             push_event(
@@ -214,50 +512,42 @@ impl ActiveRun {
             return;
         }
 
-        self.node_index += 1;
-        let node_type = self.current_node_type().unwrap_or(NodeType::Battle);
-        let node_type_label = match node_type {
-            NodeType::Boss => "Boss",
-            _ => "Battle",
+        if options.len() > 1 {
+            self.awaiting_path = true;
+            return;
+        }
+        self.enter_node(options[0], events);
+    }
+
+    pub(crate) fn enter_node(&mut self, node_id: u32, events: &mut Vec<String>) {
+        let Some(node) = self.map.node(node_id) else {
+            return;
         };
+        let node_type = node.node_type;
+        self.run.floor = node.floor;
+        self.node_index += 1;
+        self.path.push(node_id);
 
         push_event(
             events,
             Event::NodeStart {
                 node_index: self.node_index,
-                node_type: node_type_label,
+                node_type: node_type.as_str(),
+                floor: self.run.floor,
             },
         );
 
+        if node_type.is_combat() {
+            self.start_battle(node_type, events);
+        } else {
+            self.open_node_offer(node_type, events);
+        }
+    }
+
+    fn start_battle(&mut self, node_type: NodeType, events: &mut Vec<String>) {
         self.battle_index += 1;
-        let (battle_state, enemy_name) = match node_type {
-            NodeType::Boss => (
-                create_battle(
-                    self.run.player_hp,
-                    self.run.player_max_hp,
-                    self.run.player_atk,
-                    self.run.player_speed,
-                    1,
-                    220.0,
-                    14,
-                    32.0,
-                ),
-                "Overstack Core",
-            ),
-            _ => (
-                create_battle(
-                    self.run.player_hp,
-                    self.run.player_max_hp,
-                    self.run.player_atk,
-                    self.run.player_speed,
-                    1,
-                    84.0,
-                    11,
-                    28.0,
-                ),
-                "Rogue Drone",
-            ),
-        };
+        let encounter = pick_encounter(node_type, self.run.floor, &mut self.run.rng);
+        let battle_state = create_battle(&self.run.party, &encounter_enemies(encounter));
 
         self.current_battle = Some(ActiveBattle::new(battle_state));
 
@@ -265,17 +555,13 @@ impl ActiveRun {
             events,
             Event::BattleStart {
                 battle_index: self.battle_index,
-                enemy_name,
+                enemy_name: encounter.name,
             },
         );
 
-        let context = TriggerContext {
-            trigger_type: TriggerType::OnBattleStart,
-            src_idx: None,
-            dst_idx: None,
-            applied_status: None,
-        };
+        let context = TriggerContext::new(TriggerType::OnBattleStart, None, None);
         self.process_trait_triggers(context, 0, events);
+        self.plan_enemy_intents(events);
     }
 }
 
@@ -297,72 +583,138 @@ pub fn reset_run(handle: u32) -> bool {
 #[wasm_bindgen]
 pub fn step(handle: u32, dt: f32, player_action: Option<ActionInput>) -> StepResult {
     manager::with_run_mut(handle, |run| {
+        let target = player_action.as_ref().and_then(|a| a.target);
         let action = player_action.map(|a| a.to_kind());
-        run.step_once(dt, action)
+        run.step_once_with_target(dt, action, target)
     })
     .unwrap_or_else(|| StepResult {
         events: Vec::new(),
         need_input: false,
+        input_kind: String::new(),
+        input_unit_id: None,
         ended: true,
         error: format!("invalid_handle:{handle}"),
     })
 }
 
+fn parse_action(action_kind: &str, action_arg: i32) -> Result<Option<ActionKind>, ()> {
+    match action_kind {
+        "none" | "" => Ok(None),
+        "basic" => Ok(Some(ActionKind::BasicAttack)),
+        "skill" => Ok(Some(ActionKind::SkillSlot(action_arg.clamp(0, 3) as u32))),
+        "choice" => Ok(Some(ActionKind::NodeChoice(action_arg.max(0) as u32))),
+        "path" => Ok(Some(ActionKind::PathChoice(action_arg.max(0) as u32))),
+        "trait" => Ok(Some(ActionKind::TraitChoice(action_arg.max(0) as u32))),
+        _ => Err(()),
+    }
+}
+
 #[wasm_bindgen]
 pub fn step_with_action(handle: u32, dt: f32, action_kind: &str, action_arg: i32) -> StepResult {
+    step_with_target(handle, dt, action_kind, action_arg, -1)
+}
+
+/// Same as `step_with_action`, aimed at battle unit `target_id` (negative = random target).
+#[wasm_bindgen]
+pub fn step_with_target(
+    handle: u32,
+    dt: f32,
+    action_kind: &str,
+    action_arg: i32,
+    target_id: i32,
+) -> StepResult {
     manager::with_run_mut(handle, |run| {
-        let action = match action_kind {
-            "none" | "" => None,
-            "basic" => Some(ActionKind::BasicAttack),
-            "skill" => Some(ActionKind::SkillSlot(action_arg.clamp(0, 3) as u32)),
-            _ => {
-                return StepResult {
-                    events: Vec::new(),
-                    need_input: false,
-                    ended: run.ended,
-                    error: format!("invalid_action:{action_kind}"),
-                };
-            }
+        let Ok(action) = parse_action(action_kind, action_arg) else {
+            return StepResult {
+                events: Vec::new(),
+                need_input: false,
+                input_kind: String::new(),
+                input_unit_id: None,
+                ended: run.ended,
+                error: format!("invalid_action:{action_kind}"),
+            };
         };
 
-        run.step_once(dt, action)
+        let target = u32::try_from(target_id).ok();
+        run.step_once_with_target(dt, action, target)
     })
     .unwrap_or_else(|| StepResult {
         events: Vec::new(),
         need_input: false,
+        input_kind: String::new(),
+        input_unit_id: None,
         ended: true,
         error: format!("invalid_handle:{handle}"),
     })
 }
 
+/// Unit ids the player may aim `basic` / `skill` (slot `action_arg`) at right now.
+#[wasm_bindgen]
+pub fn get_legal_targets(handle: u32, action_kind: &str, action_arg: i32) -> Vec<u32> {
+    manager::with_run(handle, |run| match parse_action(action_kind, action_arg) {
+        Ok(Some(action)) => run.legal_target_ids(action),
+        _ => Vec::new(),
+    })
+    .unwrap_or_default()
+}
+
 #[wasm_bindgen]
 pub fn get_snapshot(handle: u32) -> Snapshot {
     manager::with_run(handle, |run| run.snapshot()).unwrap_or_else(|| Snapshot {
         run_state: "ended".to_string(),
         run_result: "invalid_handle".to_string(),
         node_index: 0,
+        node_type: String::new(),
+        floor: 0,
         battle_index: 0,
+        gold: 0,
         elapsed_time: 0.0,
-        player: UnitSnapshot {
-            hp: 0.0,
-            max_hp: 0.0,
-            action_gauge: 0.0,
-            statuses: Vec::new(),
-        },
-        enemy: UnitSnapshot {
-            hp: 0.0,
-            max_hp: 0.0,
-            action_gauge: 0.0,
-            statuses: Vec::new(),
-        },
+        party: Vec::new(),
+        enemies: Vec::new(),
     })
 }
 
 #[wasm_bindgen]
+/// Skill slot names of the hero waiting for input; empty while no hero is waiting.
 pub fn get_player_skills(handle: u32) -> Vec<String> {
-    manager::with_run(handle, |_| player_skill_names()).unwrap_or_default()
+    manager::with_run(handle, |run| run.player_skill_names()).unwrap_or_default()
+}
+
+#[wasm_bindgen]
+pub fn get_selectable_hero_ids() -> Vec<String> {
+    selectable_hero_ids()
+}
+
+#[wasm_bindgen]
+pub fn get_selectable_hero_names() -> Vec<String> {
+    selectable_hero_names()
+}
+
+/// Replaces the party before the run starts; up to `MAX_PARTY_SIZE` distinct heroes.
+#[wasm_bindgen]
+pub fn set_party(handle: u32, hero_ids: Vec<String>) -> bool {
+    manager::with_run_mut(handle, |run| run.set_party(&hero_ids)).unwrap_or(false)
+}
+
+/// Choice labels for the Event/Shop/Rest node the run is paused at. Empty during battles.
+#[wasm_bindgen]
+pub fn get_node_choices(handle: u32) -> Vec<String> {
+    manager::with_run(handle, |run| run.node_choice_labels()).unwrap_or_default()
 }
 
+/// "Name: description" labels for the traits offered by the open post-victory draft.
+#[wasm_bindgen]
+pub fn get_trait_choices(handle: u32) -> Vec<String> {
+    manager::with_run(handle, |run| run.trait_choice_labels()).unwrap_or_default()
+}
+
+/// Full run map with visited/selectable flags so the site can draw it.
+#[wasm_bindgen]
+pub fn get_run_map(handle: u32) -> Vec<MapNodeSnapshot> {
+    manager::with_run(handle, |run| run.map_snapshot()).unwrap_or_default()
+}
+
+/// Owned trait names with their levels, e.g. `"Cinder Scholar Lv2"`.
 #[wasm_bindgen]
 pub fn get_active_traits(handle: u32) -> Vec<String> {
     manager::with_run(handle, |run| run.active_trait_names()).unwrap_or_default()
@@ -378,19 +730,42 @@ pub fn get_selectable_trait_ids() -> Vec<String> {
     selectable_trait_ids()
 }
 
+/// Adds a trait or upgrades an owned one; see `ActiveRun::add_active_trait` for failures.
 #[wasm_bindgen]
-pub fn set_active_trait(handle: u32, trait_id: &str) -> bool {
-    manager::with_run_mut(handle, |run| run.set_single_active_trait(trait_id)).unwrap_or(false)
+pub fn add_active_trait(handle: u32, trait_id: &str) -> bool {
+    manager::with_run_mut(handle, |run| run.add_active_trait(trait_id)).unwrap_or(false)
 }
 
 #[cfg(test)]
-mod tests {
-    use super::{ActionKind, ActiveRun, TRAIT_CHAIN_DEPTH_MAX};
+pub(crate) mod tests {
+    use super::{
+        ActionKind, ActiveBattle, ActiveRun, ActiveTrait, TriggerContext, TriggerType,
+        TRAIT_CHAIN_DEPTH_MAX,
+    };
+    use crate::battle::create_battle;
+    use crate::enemy_spec::{EnemySpec, EMBER_WISP, RUST_SENTINEL, SCRAP_HOUND, VOLT_STALKER};
+    use crate::engine::damage_system::DamageHit;
+    use crate::model::NodeType;
+    use crate::skill::{DamageType, StatType, StatusType, BASIC_ATTACK, EMBER_LASH, FROST_BITE};
+    use crate::trait_spec::{
+        trait_by_id, CINDER_SCHOLAR, KEEN_EDGE, MAX_ACTIVE_TRAITS, OVERCHARGE, TRAIT_DRAFT_POOL,
+        TRAIT_OFFER_SIZE,
+    };
+
+    /// Run with its default party already in a battle against `enemies`.
+    pub(crate) fn run_against(seed: u64, enemies: &[&'static EnemySpec]) -> ActiveRun {
+        let mut run = ActiveRun::new(seed, 12);
+        run.current_battle = Some(ActiveBattle::new(create_battle(&run.run.party, enemies)));
+        run
+    }
 
     #[test]
     fn ember_lash_applies_burn_sometimes_with_fixed_seed() {
         let mut run = ActiveRun::new(20260213, 1);
         let mut burn_applied = 0_u32;
+        run.step_once(0.0, None);
+        // A certain proc keeps the result independent of how many rolls came before.
+        run.add_proc_bonus(0, 1.0);
 
         for _ in 0..50 {
             let result = run.step_once(0.15, None);
@@ -422,14 +797,20 @@ mod tests {
             }
         }
 
-        assert!(burn_applied > 0, "expected Burn to be applied at least once");
+        assert!(
+            burn_applied > 0,
+            "expected Burn to be applied at least once"
+        );
     }
 
     #[test]
     fn trait_triggered_event_emitted_with_fixed_seed() {
         let mut run = ActiveRun::new(424242, 1);
-        assert!(run.set_single_active_trait("overcharge"));
+        assert!(run.add_active_trait("overcharge"));
         let mut triggered_count = 0_u32;
+        run.step_once(0.0, None);
+        // Arc Jolt's Shock always lands, so Overcharge fires whatever the seed rolled before.
+        run.add_proc_bonus(0, 1.0);
 
         for _ in 0..50 {
             let result = run.step_once(0.15, None);
@@ -453,13 +834,16 @@ mod tests {
             }
         }
 
-        assert!(triggered_count > 0, "expected at least one trait trigger event");
+        assert!(
+            triggered_count > 0,
+            "expected at least one trait trigger event"
+        );
     }
 
     #[test]
     fn trait_chain_depth_guard_keeps_event_count_bounded() {
         let mut run = ActiveRun::new(777, 1);
-        assert!(run.set_single_active_trait("overcharge"));
+        assert!(run.add_active_trait("overcharge"));
         let mut max_events = 0_usize;
 
         for _ in 0..40 {
@@ -480,4 +864,565 @@ mod tests {
             TRAIT_CHAIN_DEPTH_MAX
         );
     }
+
+    #[test]
+    fn event_node_pauses_for_choice_and_resolves() {
+        let mut run = ActiveRun::new(99, 12);
+        run.map.nodes[0].node_type = NodeType::Event;
+
+        let paused = run.step_once(0.15, None);
+        assert_eq!(run.current_node_type(), Some(NodeType::Event));
+        assert!(paused.need_input);
+        assert_eq!(paused.input_kind, "node_choice");
+        assert!(!run.node_choice_labels().is_empty());
+
+        let wrong = run.step_once(0.0, Some(ActionKind::BasicAttack));
+        assert!(!wrong.error.is_empty());
+        assert!(run.pending_node.is_some());
+
+        let resolved = run.step_once(0.0, Some(ActionKind::NodeChoice(0)));
+        assert!(resolved.error.is_empty());
+        assert!(resolved
+            .events
+            .iter()
+            .any(|line| line.contains("\"kind\":\"NodeChoiceMade\"")));
+        assert!(run.pending_node.is_none());
+        assert!(run.current_battle.is_some() || run.awaiting_path);
+    }
+
+    #[test]
+    fn shop_stays_open_until_leave_and_checks_gold() {
+        let mut run = ActiveRun::new(5, 12);
+        run.map.nodes[0].node_type = NodeType::Shop;
+        run.step_once(0.15, None);
+        assert_eq!(run.current_node_type(), Some(NodeType::Shop));
+
+        let choice_count = run.node_choice_labels().len();
+        let broke = run.step_once(0.0, Some(ActionKind::NodeChoice(0)));
+        assert!(broke.error.starts_with("insufficient_gold"));
+
+        run.run.gold = 100;
+        let bought = run.step_once(0.0, Some(ActionKind::NodeChoice(0)));
+        assert!(bought.error.is_empty());
+        assert!(run.run.gold < 100);
+        assert_eq!(run.node_choice_labels().len(), choice_count - 1);
+
+        let leave_idx = (choice_count - 2) as u32;
+        run.step_once(0.0, Some(ActionKind::NodeChoice(leave_idx)));
+        assert!(run.pending_node.is_none());
+    }
+
+    #[test]
+    fn branching_node_waits_for_valid_path_choice() {
+        let mut run = ActiveRun::new(11, 12);
+        run.map.nodes[0].node_type = NodeType::Rest;
+        run.step_once(0.15, None);
+        let after_rest = run.step_once(0.0, Some(ActionKind::NodeChoice(0)));
+
+        let options = run.next_node_options();
+        assert!(
+            options.len() > 1,
+            "floor after the entry node should branch"
+        );
+        assert!(after_rest.need_input);
+        assert_eq!(after_rest.input_kind, "path_choice");
+        assert!(run.map_snapshot().iter().filter(|n| n.selectable).count() > 1);
+
+        let invalid = run.step_once(0.0, Some(ActionKind::PathChoice(0)));
+        assert!(invalid.error.starts_with("invalid_path"));
+
+        let picked = options[options.len() - 1];
+        let moved = run.step_once(0.0, Some(ActionKind::PathChoice(picked)));
+        assert!(moved.error.is_empty());
+        assert_eq!(run.path.last().copied(), Some(picked));
+        assert_eq!(run.node_index, 2);
+    }
+
+    #[test]
+    fn victories_draft_trait_rewards_up_to_the_cap() {
+        let mut run = ActiveRun::new(21, 12);
+        assert!(run.add_active_trait("overcharge"));
+        run.step_once(0.15, None);
+        assert!(run.current_battle.is_some());
+
+        let mut events = Vec::new();
+        run.finalize_battle("win", &mut events);
+        assert!(events
+            .iter()
+            .any(|l| l.contains("\"kind\":\"TraitOffered\"")));
+        assert_eq!(run.trait_choice_labels().len(), TRAIT_OFFER_SIZE);
+
+        let paused = run.step_once(0.15, None);
+        assert_eq!(paused.input_kind, "trait_choice");
+        assert!(run.current_battle.is_none() && run.pending_node.is_none());
+
+        let invalid = run.step_once(0.0, Some(ActionKind::TraitChoice(TRAIT_OFFER_SIZE as u32)));
+        assert!(invalid.error.starts_with("invalid_choice"));
+
+        let offered = run.pending_traits[1];
+        let picked = run.step_once(0.0, Some(ActionKind::TraitChoice(1)));
+        assert!(picked.error.is_empty());
+        assert!(picked
+            .events
+            .iter()
+            .any(|l| l.contains("\"kind\":\"TraitAcquired\"")));
+        let expected_level = if offered == OVERCHARGE.id { 2 } else { 1 };
+        let gained = run.active_traits.iter().find(|t| t.id == offered);
+        assert_eq!(gained.map(|t| t.level), Some(expected_level));
+        assert!(run.pending_traits.is_empty());
+
+        run.active_traits = TRAIT_DRAFT_POOL[..MAX_ACTIVE_TRAITS]
+            .iter()
+            .map(|id| ActiveTrait {
+                id,
+                level: trait_by_id(id).map_or(1, |t| t.max_level),
+            })
+            .collect();
+        assert!(!run.add_active_trait(TRAIT_DRAFT_POOL[MAX_ACTIVE_TRAITS]));
+        assert!(!run.add_active_trait(TRAIT_DRAFT_POOL[0]));
+        let mut capped = Vec::new();
+        run.open_trait_offer(&mut capped);
+        assert!(capped.is_empty() && run.pending_traits.is_empty());
+    }
+
+    #[test]
+    fn redrafting_a_trait_levels_it_up_and_scales_its_effects() {
+        let mut run = run_against(4, &[&EMBER_WISP]);
+        assert!(run.add_active_trait(CINDER_SCHOLAR.id));
+        assert!(run.add_active_trait(KEEN_EDGE.id));
+        let crit_lv1 = run.effective_stat(0, StatType::CritChance);
+
+        assert!(run.add_active_trait(CINDER_SCHOLAR.id));
+        assert!(run.add_active_trait(KEEN_EDGE.id));
+        assert!(run.add_active_trait(CINDER_SCHOLAR.id));
+        assert!(!run.add_active_trait(CINDER_SCHOLAR.id));
+        assert_eq!(
+            run.active_trait_names(),
+            vec![
+                "Cinder Scholar Lv3".to_string(),
+                "Keen Edge Lv2".to_string()
+            ]
+        );
+
+        // Keen Edge: +10% crit at level 1, +15% at level 2.
+        let crit_lv2 = run.effective_stat(0, StatType::CritChance);
+        assert!((crit_lv2 - crit_lv1 - 0.05).abs() < 1e-4);
+
+        // Cinder Scholar: Burn power x1.25 at level 1, x1.75 at level 3.
+        let context = TriggerContext {
+            applied_status: Some(StatusType::Burn),
+            ..TriggerContext::new(TriggerType::OnStatusApplied, Some(0), Some(1))
+        };
+        run.process_trait_triggers(context, 0, &mut Vec::new());
+        assert!((run.status_power_mul_for(0, StatusType::Burn) - 1.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rotation_policy_cycles_enemy_loadout() {
+        let mut run = run_against(3, &[&RUST_SENTINEL]);
+        let used: Vec<&str> = (0..4).map(|_| run.choose_enemy_skill(1, 0).id).collect();
+        assert_eq!(
+            used,
+            [
+                "basic_attack",
+                "basic_attack",
+                "crushing_slam",
+                "basic_attack"
+            ]
+        );
+    }
+
+    #[test]
+    fn enemy_skills_apply_statuses_to_player() {
+        let mut run = run_against(9, &[&EMBER_WISP]);
+        let mut burned = false;
+
+        for _ in 0..200 {
+            let result = run.step_once(0.1, None);
+            let input = if result.need_input {
+                run.step_once(0.0, Some(ActionKind::BasicAttack)).events
+            } else {
+                Vec::new()
+            };
+            burned |= result.events.iter().chain(input.iter()).any(|line| {
+                line.contains("\"kind\":\"StatusApplied\"")
+                    && line.contains("\"dst\":\"player\"")
+                    && line.contains("\"status\":\"Burn\"")
+            });
+            if burned || run.current_battle.is_none() {
+                break;
+            }
+        }
+
+        assert!(burned, "Ember Wisp should burn the player with Scorch");
+    }
+
+    #[test]
+    fn repeated_intent_is_not_announced_again() {
+        let mut run = run_against(4, &[&RUST_SENTINEL]);
+        let mut log = Vec::new();
+        run.plan_enemy_intent(1, &mut log);
+        assert_eq!(log.len(), 1);
+
+        log.clear();
+        run.take_enemy_intent(1, &mut log)
+            .expect("intent should be usable");
+        run.plan_enemy_intent(1, &mut log);
+        assert!(
+            log.is_empty(),
+            "the rotation's second basic attack repeats the intent"
+        );
+        assert!(run.snapshot().enemies[0].intent.is_some());
+    }
+
+    #[test]
+    fn intent_on_a_fallen_hero_is_retargeted() {
+        let mut run = ActiveRun::new(8, 12);
+        assert!(run.set_party(&["vanguard".to_string(), "arcanist".to_string()]));
+        run.current_battle = Some(ActiveBattle::new(create_battle(
+            &run.run.party,
+            &[&RUST_SENTINEL],
+        )));
+        let mut log = Vec::new();
+        run.plan_enemy_intent(2, &mut log);
+        let aimed = run
+            .runtime_ref(2)
+            .and_then(|r| r.intent)
+            .expect("intent")
+            .target_idx;
+
+        log.clear();
+        let hit = DamageHit::new(2, aimed, "test", 9999.0, DamageType::Pure);
+        run.apply_damage(hit, 0, &mut log);
+        let intent = run.runtime_ref(2).and_then(|r| r.intent).expect("intent");
+        assert_eq!(intent.target_idx, 1 - aimed);
+        assert_eq!(intent.skill.id, "basic_attack");
+        assert!(log
+            .iter()
+            .any(|line| line.contains("\"kind\":\"IntentChanged\"")));
+    }
+
+    #[test]
+    fn enemy_intent_is_telegraphed_before_it_is_used() {
+        let mut run = run_against(4, &[&RUST_SENTINEL]);
+        let mut log = Vec::new();
+        run.plan_enemy_intents(&mut log);
+        assert!(log
+            .iter()
+            .any(|line| line.contains("\"kind\":\"IntentChanged\"")));
+
+        let intent = run.snapshot().enemies[0]
+            .intent
+            .clone()
+            .expect("enemy should telegraph");
+        assert_eq!(intent.skill_id, "basic_attack");
+        assert_eq!(intent.target, "player");
+        assert_eq!(intent.estimated_damage, RUST_SENTINEL.atk as f32);
+
+        for _ in 0..300 {
+            let result = run.step_once(0.1, None);
+            log.extend(result.events);
+            if result.need_input {
+                log.extend(run.step_once(0.0, Some(ActionKind::BasicAttack)).events);
+            }
+            if run.current_battle.is_none() {
+                break;
+            }
+        }
+
+        let announced = log
+            .iter()
+            .position(|line| line.contains("\"skill_id\":\"crushing_slam\""))
+            .expect("rotation should telegraph Crushing Slam");
+        let used = log
+            .iter()
+            .position(|line| line.contains("\"action_name\":\"Crushing Slam\""))
+            .expect("Crushing Slam should be used");
+        assert!(announced < used);
+    }
+
+    #[test]
+    fn multi_enemy_battle_honors_explicit_target() {
+        let mut run = run_against(7, &[&SCRAP_HOUND, &SCRAP_HOUND]);
+        let ids: Vec<u32> = run.snapshot().enemies.iter().map(|e| e.unit_id).collect();
+        assert_eq!(ids, [1, 2]);
+
+        let mut hits_on_second = 0;
+        for _ in 0..200 {
+            let result = run.step_once_with_target(0.1, None, None);
+            if result.need_input {
+                let acted = run.step_once_with_target(0.0, Some(ActionKind::BasicAttack), Some(2));
+                for line in &acted.events {
+                    if line.contains("\"kind\":\"DamageDealt\"")
+                        && line.contains("\"src\":\"player\"")
+                    {
+                        assert!(line.contains("\"dst_id\":2"), "unexpected target: {line}");
+                        hits_on_second += 1;
+                    }
+                }
+            }
+            if run.snapshot().enemies[1].hp <= 0.0 || run.current_battle.is_none() {
+                break;
+            }
+        }
+
+        assert!(hits_on_second > 0);
+        assert!(run
+            .snapshot()
+            .enemies
+            .iter()
+            .any(|e| e.unit_id == 1 && e.hp > 0.0));
+    }
+
+    #[test]
+    fn invalid_targets_are_rejected_with_reasons() {
+        let mut run = run_against(6, &[&SCRAP_HOUND, &SCRAP_HOUND]);
+        assert!(run.legal_target_ids(ActionKind::BasicAttack).is_empty());
+        let early = run.step_once(0.0, Some(ActionKind::BasicAttack));
+        assert_eq!(early.error, "invalid_action:no_hero_waiting");
+
+        for _ in 0..200 {
+            if run.step_once(0.1, None).need_input {
+                break;
+            }
+        }
+        assert!(run.waiting_hero_idx().is_some());
+        assert_eq!(run.legal_target_ids(ActionKind::SkillSlot(1)), [1, 2]);
+
+        if let Some(state) = run.state_mut() {
+            state.units[2].hp = 0.0;
+        }
+        assert_eq!(run.legal_target_ids(ActionKind::BasicAttack), [1]);
+
+        let cases = [
+            (9, "invalid_target:unknown_unit:9"),
+            (0, "invalid_target:wrong_team:0"),
+            (2, "invalid_target:dead:2"),
+        ];
+        for (target, expected) in cases {
+            let result =
+                run.step_once_with_target(0.0, Some(ActionKind::BasicAttack), Some(target));
+            assert_eq!(result.error, expected);
+        }
+
+        let ok = run.step_once_with_target(0.1, Some(ActionKind::BasicAttack), Some(1));
+        assert!(ok.error.is_empty());
+    }
+
+    #[test]
+    fn party_heroes_take_their_own_turns_with_their_own_skills() {
+        let mut run = ActiveRun::new(21, 12);
+        assert!(!run.set_party(&["vanguard".to_string(), "vanguard".to_string()]));
+        assert!(run.set_party(&["vanguard".to_string(), "arcanist".to_string()]));
+        run.current_battle = Some(ActiveBattle::new(create_battle(
+            &run.run.party,
+            &[&RUST_SENTINEL],
+        )));
+        assert_eq!(run.snapshot().party.len(), 2);
+        assert_eq!(run.snapshot().enemies[0].unit_id, 2);
+
+        let mut acted: Vec<u32> = Vec::new();
+        for _ in 0..200 {
+            let result = run.step_once(0.1, None);
+            if let Some(unit_id) = result.input_unit_id {
+                let expected = run.player_skill_names()[0].clone();
+                let acted_result = run.step_once(0.0, Some(ActionKind::SkillSlot(0)));
+                let used = acted_result
+                    .events
+                    .iter()
+                    .find(|line| line.contains("\"kind\":\"ActionUsed\""))
+                    .expect("hero should act");
+                assert!(used.contains(&format!("\"actor_id\":{unit_id}")));
+                assert!(used.contains(&format!("\"action_name\":\"{expected}\"")));
+                if !acted.contains(&unit_id) {
+                    acted.push(unit_id);
+                }
+            }
+            if acted.len() == 2 || run.current_battle.is_none() {
+                break;
+            }
+        }
+
+        acted.sort_unstable();
+        assert_eq!(acted, [0, 1]);
+        assert!(!run.set_party(&["reaver".to_string()]));
+    }
+
+    #[test]
+    fn skill_cooldowns_and_energy_gate_player_actions() {
+        let mut run = run_against(13, &[&RUST_SENTINEL]);
+        let ruin = ActionKind::SkillSlot(3);
+        if let Some(state) = run.state_mut() {
+            state.units[1].max_hp = 9999.0;
+            state.units[1].hp = 9999.0;
+        }
+
+        let wait_for_turn = |run: &mut ActiveRun| {
+            for _ in 0..200 {
+                if run.step_once(0.1, None).need_input {
+                    return;
+                }
+            }
+            panic!("hero never got a turn");
+        };
+
+        wait_for_turn(&mut run);
+        let broke = run.step_once(0.0, Some(ruin));
+        assert_eq!(
+            broke.error,
+            "invalid_action:insufficient_energy:ruin_strike:2/3"
+        );
+        assert!(broke.need_input, "rejected actions keep the turn open");
+        let hero = &run.snapshot().party[0];
+        assert_eq!(hero.skills[3].skill_id, "ruin_strike");
+        assert!(!hero.skills[3].ready);
+
+        run.step_once(0.0, Some(ActionKind::BasicAttack));
+        wait_for_turn(&mut run);
+        assert!(run.snapshot().party[0].skills[3].ready);
+        let used = run.step_once(0.0, Some(ruin));
+        assert!(used.error.is_empty());
+        assert!(used
+            .events
+            .iter()
+            .any(|l| l.contains("\"action_name\":\"Ruin Strike\"")));
+
+        let hero = &run.snapshot().party[0];
+        assert_eq!(hero.energy, 1);
+        assert_eq!(hero.skills[3].cooldown_remaining, 2);
+
+        wait_for_turn(&mut run);
+        let cooling = run.step_once(0.0, Some(ruin));
+        assert_eq!(cooling.error, "invalid_action:on_cooldown:ruin_strike:2");
+    }
+
+    #[test]
+    fn queued_skill_is_checked_against_the_hero_that_acts() {
+        let mut run = ActiveRun::new(14, 12);
+        assert!(run.set_party(&["vanguard".to_string(), "arcanist".to_string()]));
+        run.current_battle = Some(ActiveBattle::new(create_battle(
+            &run.run.party,
+            &[&RUST_SENTINEL],
+        )));
+        // The Vanguard is waiting, but the faster Arcanist overtakes it while time passes.
+        if let Some(state) = run.state_mut() {
+            state.units[0].action_gauge = 100.0;
+            state.units[1].action_gauge = 99.9;
+            state.units[2].action_gauge = 0.0;
+        }
+        if let Some(runtime) = run.runtime_mut(1) {
+            runtime.energy = 0;
+        }
+        run.waiting_for_input = true;
+        assert_eq!(run.waiting_hero_idx(), Some(0));
+
+        let result = run.step_once(0.1, Some(ActionKind::SkillSlot(0)));
+        assert_eq!(
+            result.error,
+            "invalid_action:insufficient_energy:frost_bite:0/1"
+        );
+        assert!(!result
+            .events
+            .iter()
+            .any(|l| l.contains("\"kind\":\"ActionUsed\"")));
+        assert!(result.need_input);
+        assert_eq!(run.waiting_hero_idx(), Some(1));
+    }
+
+    #[test]
+    fn might_and_trait_modifiers_change_effective_stats() {
+        let mut run = run_against(2, &[&SCRAP_HOUND]);
+        let hound = &run.snapshot().enemies[0];
+        assert_eq!(hound.base_speed, SCRAP_HOUND.speed);
+        assert!((hound.speed - SCRAP_HOUND.speed * 1.1).abs() < 1e-3);
+
+        let base_hit = run.estimate_skill_damage(0, &BASIC_ATTACK);
+        let mut events = Vec::new();
+        run.apply_status(0, 0, StatusType::Might, 1.0, 5.0, 2, 2.0, 0, &mut events);
+
+        let hero = &run.snapshot().party[0];
+        assert_eq!(hero.base_atk, 17.0);
+        assert!((hero.atk - 17.0 * 1.2).abs() < 1e-3);
+        assert!(run.estimate_skill_damage(0, &BASIC_ATTACK) > base_hit);
+    }
+
+    #[test]
+    fn skill_tags_pick_the_damage_type_that_enemy_defenses_mitigate() {
+        assert_eq!(DamageType::from_tags(EMBER_LASH.tags), DamageType::Fire);
+        assert_eq!(DamageType::from_tags(FROST_BITE.tags), DamageType::Ice);
+        assert_eq!(
+            DamageType::from_tags(BASIC_ATTACK.tags),
+            DamageType::Physical
+        );
+
+        let mut run = run_against(3, &[&EMBER_WISP]);
+        let mut hit = |damage_type| {
+            let before = run.snapshot().enemies[0].hp;
+            run.apply_damage(
+                DamageHit::new(0, 1, "test", 10.0, damage_type),
+                0,
+                &mut Vec::new(),
+            );
+            before - run.snapshot().enemies[0].hp
+        };
+        assert!((hit(DamageType::Fire) - 4.0).abs() < 1e-3, "resists fire");
+        assert!((hit(DamageType::Ice) - 15.0).abs() < 1e-3, "weak to ice");
+        assert!((hit(DamageType::Physical) - 10.0).abs() < 1e-3, "no armor");
+
+        let mut run = run_against(3, &[&RUST_SENTINEL]);
+        let hit = DamageHit::new(0, 1, "test", 14.0, DamageType::Physical);
+        run.apply_damage(hit, 0, &mut Vec::new());
+        let sentinel = &run.snapshot().enemies[0];
+        assert!(
+            (RUST_SENTINEL.max_hp - sentinel.hp - 10.0).abs() < 1e-3,
+            "armor 40"
+        );
+        assert_eq!(sentinel.armor, RUST_SENTINEL.armor);
+        assert_eq!(sentinel.resistances[0].damage_type, "lightning");
+    }
+
+    #[test]
+    fn skill_hits_roll_crits_and_misses() {
+        let mut run = run_against(5, &[&VOLT_STALKER]);
+        run.active_traits = vec![ActiveTrait::new(KEEN_EDGE.id)];
+        if let Some(state) = run.state_mut() {
+            for unit in &mut state.units {
+                unit.max_hp = 9999.0;
+                unit.hp = 9999.0;
+            }
+        }
+        let hero = &run.snapshot().party[0];
+        assert!((hero.crit_chance - 0.15).abs() < 1e-3);
+        assert!((run.hit_chance(0, 1) - (1.0 - VOLT_STALKER.evasion)).abs() < 1e-3);
+
+        let mut log = Vec::new();
+        for _ in 0..1500 {
+            let result = run.step_once(0.1, None);
+            log.extend(result.events);
+            if result.need_input {
+                log.extend(run.step_once(0.0, Some(ActionKind::BasicAttack)).events);
+            }
+        }
+        let hero_hits: Vec<&String> = log
+            .iter()
+            .filter(|l| l.contains("\"kind\":\"DamageDealt\"") && l.contains("\"src_id\":0"))
+            .collect();
+        let crits = hero_hits
+            .iter()
+            .filter(|l| l.contains("\"crit\":true"))
+            .count();
+        let misses: Vec<&&String> = hero_hits
+            .iter()
+            .filter(|l| l.contains("\"miss\":true"))
+            .collect();
+        assert!(
+            crits > 0 && !misses.is_empty(),
+            "crits={crits} misses={}",
+            misses.len()
+        );
+        assert!(misses.iter().all(|l| l.contains("\"amount\":0.00,")));
+        assert!(log
+            .iter()
+            .any(|l| l.contains("\"trigger_type\":\"OnCrit\"")));
+    }
 }
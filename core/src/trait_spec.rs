@@ -1,9 +1,12 @@
-use crate::skill::{Condition, EffectSpec, EffectTarget, StatusType};
+use crate::skill::{
+    Condition, DamageModifier, DamageSide, DamageStage, EffectSpec, EffectTarget, StatModifier,
+    StatType, StatusType,
+};
 
 pub type TraitId = &'static str;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 pub enum TriggerType {
     OnBattleStart,
     OnTurnStart,
@@ -12,8 +15,21 @@ pub enum TriggerType {
     OnStatusApplied,
     OnStatusTick,
     OnBattleEnd,
+    OnHeal,
+    OnCrit,
+    OnMiss,
+    OnTurnEnd,
+    /// Same source and target as `OnDamageDealt`, for traits reacting to being hit.
+    OnDamageTaken,
+    OnKill,
+    OnStatusExpired,
+    /// The target's HP ratio fell to or below one of `HP_THRESHOLDS`, carried in `amount`.
+    OnHpThreshold,
 }
 
+/// HP ratios that fire `OnHpThreshold` once each time a hit or tick crosses them.
+pub const HP_THRESHOLDS: [f32; 2] = [0.5, 0.25];
+
 impl TriggerType {
     pub fn as_str(self) -> &'static str {
         match self {
@@ -24,6 +40,14 @@ impl TriggerType {
             TriggerType::OnStatusApplied => "OnStatusApplied",
             TriggerType::OnStatusTick => "OnStatusTick",
             TriggerType::OnBattleEnd => "OnBattleEnd",
+            TriggerType::OnHeal => "OnHeal",
+            TriggerType::OnCrit => "OnCrit",
+            TriggerType::OnMiss => "OnMiss",
+            TriggerType::OnTurnEnd => "OnTurnEnd",
+            TriggerType::OnDamageTaken => "OnDamageTaken",
+            TriggerType::OnKill => "OnKill",
+            TriggerType::OnStatusExpired => "OnStatusExpired",
+            TriggerType::OnHpThreshold => "OnHpThreshold",
         }
     }
 }
@@ -35,6 +59,33 @@ pub struct TriggerRule {
     pub effects: &'static [EffectSpec],
 }
 
+/// Draft tier of a trait; rarer traits are offered less often.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraitRarity {
+    Common,
+    Rare,
+    Epic,
+}
+
+impl TraitRarity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TraitRarity::Common => "Common",
+            TraitRarity::Rare => "Rare",
+            TraitRarity::Epic => "Epic",
+        }
+    }
+
+    /// Relative weight of the tier when the post-battle draft rolls its offer.
+    pub fn draft_weight(self) -> u32 {
+        match self {
+            TraitRarity::Common => 6,
+            TraitRarity::Rare => 3,
+            TraitRarity::Epic => 1,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[allow(dead_code)]
 pub struct TraitSpec {
@@ -42,6 +93,23 @@ pub struct TraitSpec {
     pub name: &'static str,
     pub description: &'static str,
     pub triggers: &'static [TriggerRule],
+    /// Passive modifiers for every unit owning the trait (the whole party for run traits).
+    pub stat_modifiers: &'static [StatModifier],
+    /// Damage pipeline modifiers for the same units.
+    pub damage_modifiers: &'static [DamageModifier],
+    pub rarity: TraitRarity,
+    /// Highest level drafting the trait again can reach; 1 means it never upgrades.
+    pub max_level: u32,
+    /// Share of the level-1 magnitudes gained per level above 1.
+    pub level_scaling: f32,
+}
+
+impl TraitSpec {
+    /// Multiplier on the trait's effect and modifier magnitudes at `level`.
+    pub fn level_factor(&self, level: u32) -> f32 {
+        let extra = level.clamp(1, self.max_level.max(1)) - 1;
+        1.0 + self.level_scaling * extra as f32
+    }
 }
 
 const CINDER_COND_ALL: [Condition; 3] = [
@@ -136,11 +204,147 @@ const SHATTERPOINT_RULES: [TriggerRule; 1] = [TriggerRule {
     effects: &SHATTERPOINT_RULE_EFFECTS,
 }];
 
+const PACK_HUNTER_COND_ALL: [Condition; 2] = [Condition::SrcIsOwner, Condition::TargetHPBelow(0.5)];
+const PACK_HUNTER_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::DealDamage {
+    multiplier: 0.25,
+    flat: 0.0,
+}];
+const PACK_HUNTER_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnDamageDealt,
+    condition: Condition::All(&PACK_HUNTER_COND_ALL),
+    effects: &PACK_HUNTER_RULE_EFFECTS,
+}];
+
+const PACK_HUNTER_MODIFIERS: [StatModifier; 1] = [StatModifier {
+    stat: StatType::Speed,
+    flat: 0.0,
+    percent: 0.10,
+}];
+
+const FROSTBOUND_COND_ALL: [Condition; 2] =
+    [Condition::SrcIsOwner, Condition::RandomRollBelow(0.2)];
+const FROSTBOUND_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::ApplyStatus {
+    status_type: StatusType::Freeze,
+    base_chance: 1.0,
+    duration: 2.5,
+    stacks: 1,
+    power: 1.0,
+}];
+const FROSTBOUND_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnDamageDealt,
+    condition: Condition::All(&FROSTBOUND_COND_ALL),
+    effects: &FROSTBOUND_RULE_EFFECTS,
+}];
+
+const SEARING_CORE_COND_ALL: [Condition; 2] =
+    [Condition::SrcIsOwner, Condition::RandomRollBelow(0.3)];
+const SEARING_CORE_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::ApplyStatus {
+    status_type: StatusType::Burn,
+    base_chance: 1.0,
+    duration: 4.0,
+    stacks: 1,
+    power: 1.5,
+}];
+const SEARING_CORE_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnDamageDealt,
+    condition: Condition::All(&SEARING_CORE_COND_ALL),
+    effects: &SEARING_CORE_RULE_EFFECTS,
+}];
+
+const VAMPIRIC_MODIFIERS: [StatModifier; 1] = [StatModifier {
+    stat: StatType::Lifesteal,
+    flat: 0.15,
+    percent: 0.0,
+}];
+
+const SECOND_WIND_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::ApplyStatus {
+    status_type: StatusType::Haste,
+    base_chance: 1.0,
+    duration: 3.0,
+    stacks: 1,
+    power: 1.0,
+}];
+const SECOND_WIND_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnHeal,
+    condition: Condition::SrcIsPlayer,
+    effects: &SECOND_WIND_RULE_EFFECTS,
+}];
+
+const KEEN_EDGE_MODIFIERS: [StatModifier; 1] = [StatModifier {
+    stat: StatType::CritChance,
+    flat: 0.10,
+    percent: 0.0,
+}];
+const KEEN_EDGE_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::ApplyStatus {
+    status_type: StatusType::Bleed,
+    base_chance: 1.0,
+    duration: 4.0,
+    stacks: 1,
+    power: 1.0,
+}];
+const KEEN_EDGE_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnCrit,
+    condition: Condition::SrcIsPlayer,
+    effects: &KEEN_EDGE_RULE_EFFECTS,
+}];
+
+const LAST_STAND_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::GrantBarrier {
+    target: EffectTarget::Dst,
+    amount: 20.0,
+    decay: 2.0,
+}];
+const LAST_STAND_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnHpThreshold,
+    condition: Condition::DstIsOwner,
+    effects: &LAST_STAND_RULE_EFFECTS,
+}];
+
+const BLOODTHIRST_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::SelfBuff {
+    stat: StatType::Attack,
+    amount: 1.0,
+    duration: 5.0,
+}];
+const BLOODTHIRST_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnKill,
+    condition: Condition::SrcIsOwner,
+    effects: &BLOODTHIRST_RULE_EFFECTS,
+}];
+
+const FLASHPOINT_COND_ALL: [Condition; 3] = [
+    Condition::SrcIsPlayer,
+    Condition::SkillHasTag("fire"),
+    Condition::DamageAtLeast(20.0),
+];
+const FLASHPOINT_RULE_EFFECTS: [EffectSpec; 1] = [EffectSpec::ApplyStatus {
+    status_type: StatusType::Break,
+    base_chance: 1.0,
+    duration: 4.0,
+    stacks: 1,
+    power: 1.0,
+}];
+const FLASHPOINT_RULES: [TriggerRule; 1] = [TriggerRule {
+    trigger: TriggerType::OnDamageDealt,
+    condition: Condition::All(&FLASHPOINT_COND_ALL),
+    effects: &FLASHPOINT_RULE_EFFECTS,
+}];
+
+const EXECUTIONER_DAMAGE_MODIFIERS: [DamageModifier; 1] = [DamageModifier {
+    stage: DamageStage::Multiplicative,
+    side: DamageSide::Dealt,
+    condition: Condition::TargetHPBelow(0.35),
+    value: 0.30,
+}];
+
 pub const CINDER_SCHOLAR: TraitSpec = TraitSpec {
     id: "cinder_scholar",
     name: "Cinder Scholar",
     description: "Burn applied by player enhances Burn power.",
     triggers: &CINDER_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 3,
+    level_scaling: 1.0,
 };
 
 pub const FROZEN_MOMENTUM: TraitSpec = TraitSpec {
@@ -148,6 +352,11 @@ pub const FROZEN_MOMENTUM: TraitSpec = TraitSpec {
     name: "Frozen Momentum",
     description: "Freeze application adds Break stacks.",
     triggers: &FROZEN_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 3,
+    level_scaling: 1.0,
 };
 
 pub const OVERCHARGE: TraitSpec = TraitSpec {
@@ -155,6 +364,11 @@ pub const OVERCHARGE: TraitSpec = TraitSpec {
     name: "Overcharge",
     description: "Shock application deals pure bonus damage.",
     triggers: &OVERCHARGE_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 3,
+    level_scaling: 1.0,
 };
 
 pub const HEMORRHAGE: TraitSpec = TraitSpec {
@@ -162,6 +376,11 @@ pub const HEMORRHAGE: TraitSpec = TraitSpec {
     name: "Hemorrhage",
     description: "Damage against Bleed targets gains bonus hit.",
     triggers: &HEMORRHAGE_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 3,
+    level_scaling: 1.0,
 };
 
 pub const RUTHLESS: TraitSpec = TraitSpec {
@@ -169,6 +388,11 @@ pub const RUTHLESS: TraitSpec = TraitSpec {
     name: "Ruthless",
     description: "Targets with many statuses take extra damage.",
     triggers: &RUTHLESS_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Rare,
+    max_level: 3,
+    level_scaling: 0.5,
 };
 
 pub const SHATTERPOINT: TraitSpec = TraitSpec {
@@ -176,16 +400,152 @@ pub const SHATTERPOINT: TraitSpec = TraitSpec {
     name: "Shatterpoint",
     description: "Break on Frozen targets can apply Stun.",
     triggers: &SHATTERPOINT_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Rare,
+    max_level: 2,
+    level_scaling: 0.5,
 };
 
-#[allow(dead_code)]
-pub const DEFAULT_ACTIVE_TRAITS: [TraitId; 6] = [
+pub const PACK_HUNTER: TraitSpec = TraitSpec {
+    id: "pack_hunter",
+    name: "Pack Hunter",
+    description: "Hits on wounded targets gain a bonus hit; moves 10% faster.",
+    triggers: &PACK_HUNTER_RULES,
+    stat_modifiers: &PACK_HUNTER_MODIFIERS,
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 1,
+    level_scaling: 0.0,
+};
+
+pub const FROSTBOUND: TraitSpec = TraitSpec {
+    id: "frostbound",
+    name: "Frostbound",
+    description: "Hits can Freeze the target.",
+    triggers: &FROSTBOUND_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 1,
+    level_scaling: 0.0,
+};
+
+pub const SEARING_CORE: TraitSpec = TraitSpec {
+    id: "searing_core",
+    name: "Searing Core",
+    description: "Hits can apply strong Burn.",
+    triggers: &SEARING_CORE_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 1,
+    level_scaling: 0.0,
+};
+
+pub const VAMPIRIC: TraitSpec = TraitSpec {
+    id: "vampiric",
+    name: "Vampiric",
+    description: "Hits heal the attacker for 15% of the damage dealt.",
+    triggers: &[],
+    stat_modifiers: &VAMPIRIC_MODIFIERS,
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 1,
+    level_scaling: 0.0,
+};
+
+pub const SECOND_WIND: TraitSpec = TraitSpec {
+    id: "second_wind",
+    name: "Second Wind",
+    description: "Heals from a hero also grant Haste to the healed unit.",
+    triggers: &SECOND_WIND_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Rare,
+    max_level: 3,
+    level_scaling: 0.5,
+};
+
+pub const KEEN_EDGE: TraitSpec = TraitSpec {
+    id: "keen_edge",
+    name: "Keen Edge",
+    description: "+10% crit chance; critical hits apply Bleed.",
+    triggers: &KEEN_EDGE_RULES,
+    stat_modifiers: &KEEN_EDGE_MODIFIERS,
+    damage_modifiers: &[],
+    rarity: TraitRarity::Rare,
+    max_level: 3,
+    level_scaling: 0.5,
+};
+
+pub const EXECUTIONER: TraitSpec = TraitSpec {
+    id: "executioner",
+    name: "Executioner",
+    description: "+30% damage against targets below 35% HP.",
+    triggers: &[],
+    stat_modifiers: &[],
+    damage_modifiers: &EXECUTIONER_DAMAGE_MODIFIERS,
+    rarity: TraitRarity::Epic,
+    max_level: 2,
+    level_scaling: 0.5,
+};
+
+pub const LAST_STAND: TraitSpec = TraitSpec {
+    id: "last_stand",
+    name: "Last Stand",
+    description: "Falling to 50% or 25% HP grants a 20 barrier.",
+    triggers: &LAST_STAND_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Rare,
+    max_level: 3,
+    level_scaling: 0.5,
+};
+
+pub const BLOODTHIRST: TraitSpec = TraitSpec {
+    id: "bloodthirst",
+    name: "Bloodthirst",
+    description: "Killing a unit grants Might.",
+    triggers: &BLOODTHIRST_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Common,
+    max_level: 3,
+    level_scaling: 1.0,
+};
+
+pub const FLASHPOINT: TraitSpec = TraitSpec {
+    id: "flashpoint",
+    name: "Flashpoint",
+    description: "Fire skill hits of 20+ damage apply Break.",
+    triggers: &FLASHPOINT_RULES,
+    stat_modifiers: &[],
+    damage_modifiers: &[],
+    rarity: TraitRarity::Epic,
+    max_level: 1,
+    level_scaling: 0.0,
+};
+
+/// Upper bound on traits one run can own; victories stop offering traits once it is reached.
+pub const MAX_ACTIVE_TRAITS: usize = 6;
+/// Traits offered to pick from after each victory.
+pub const TRAIT_OFFER_SIZE: usize = 3;
+
+/// Hero traits the post-battle draft rolls from.
+pub const TRAIT_DRAFT_POOL: [TraitId; 12] = [
     CINDER_SCHOLAR.id,
     FROZEN_MOMENTUM.id,
     OVERCHARGE.id,
     HEMORRHAGE.id,
     RUTHLESS.id,
     SHATTERPOINT.id,
+    SECOND_WIND.id,
+    KEEN_EDGE.id,
+    EXECUTIONER.id,
+    LAST_STAND.id,
+    BLOODTHIRST.id,
+    FLASHPOINT.id,
 ];
 
 pub const SELECTABLE_TRAITS: [TraitId; 5] = [
@@ -204,13 +564,23 @@ pub fn trait_by_id(id: &str) -> Option<&'static TraitSpec> {
         "hemorrhage" => Some(&HEMORRHAGE),
         "ruthless" => Some(&RUTHLESS),
         "shatterpoint" => Some(&SHATTERPOINT),
+        "pack_hunter" => Some(&PACK_HUNTER),
+        "frostbound" => Some(&FROSTBOUND),
+        "searing_core" => Some(&SEARING_CORE),
+        "vampiric" => Some(&VAMPIRIC),
+        "second_wind" => Some(&SECOND_WIND),
+        "keen_edge" => Some(&KEEN_EDGE),
+        "executioner" => Some(&EXECUTIONER),
+        "last_stand" => Some(&LAST_STAND),
+        "bloodthirst" => Some(&BLOODTHIRST),
+        "flashpoint" => Some(&FLASHPOINT),
         _ => None,
     }
 }
 
 pub fn active_trait_names(ids: &[TraitId]) -> Vec<String> {
     ids.iter()
-        .filter_map(|id| trait_by_id(*id))
+        .filter_map(|id| trait_by_id(id))
         .map(|t| t.name.to_string())
         .collect()
 }
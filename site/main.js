@@ -1,19 +1,28 @@
 // wasm-pack output is loaded via relative path for GitHub Pages root compatibility.
 import init, {
+  add_active_trait,
   create_run,
   destroy_run,
   get_active_traits,
+  get_legal_targets,
+  get_node_choices,
   get_player_skills,
+  get_run_map,
+  get_selectable_hero_ids,
+  get_selectable_hero_names,
   get_selectable_trait_ids,
   get_selectable_trait_names,
   get_snapshot,
+  get_trait_choices,
   reset_run,
   run_run,
-  set_active_trait,
+  set_party,
   step_with_action,
+  step_with_target,
 } from "./pkg/core.js";
 
 const seedInput = document.getElementById("seedInput");
+const partySelect = document.getElementById("partySelect");
 const startBtn = document.getElementById("startBtn");
 const resetBtn = document.getElementById("resetBtn");
 const bootStatus = document.getElementById("bootStatus");
@@ -22,9 +31,12 @@ const statusNode = document.getElementById("statusNode");
 const statusBattle = document.getElementById("statusBattle");
 const statusPlayerHp = document.getElementById("statusPlayerHp");
 const statusEnemyHp = document.getElementById("statusEnemyHp");
+const statusEnemyIntent = document.getElementById("statusEnemyIntent");
 const statusResult = document.getElementById("statusResult");
 const statusTraits = document.getElementById("statusTraits");
 const inputPrompt = document.getElementById("inputPrompt");
+const mapView = document.getElementById("mapView");
+const targetSelect = document.getElementById("targetSelect");
 
 const actionBasicBtn = document.getElementById("actionBasic");
 const actionSkillButtons = [
@@ -36,13 +48,13 @@ const actionSkillButtons = [
 
 const STEP_DT = 0.15;
 const LOOP_MS = 120;
-const MAX_NODES = 6;
+const MAX_NODES = 12;
 const MAX_LOG_LINES = 30;
 
 let currentHandle = null;
 let loopTimer = null;
 let logLines = [];
-let uiMode = "idle"; // idle | trait_select | running | need_input | ended
+let uiMode = "idle"; // idle | trait_select | running | need_input | trait_choice | node_choice | path_choice | ended
 let selectableTraitIds = [];
 
 function stopLoop() {
@@ -53,6 +65,7 @@ function stopLoop() {
 }
 
 function setActionButtonsEnabled(enabled) {
+  targetSelect.disabled = !enabled;
   actionBasicBtn.disabled = !enabled;
   for (const button of actionSkillButtons) {
     button.disabled = !enabled;
@@ -70,6 +83,28 @@ function setCombatLabels(skillNames) {
   }
 }
 
+function applySkillReadiness(hero) {
+  const skills = hero ? hero.skills : [];
+  for (let i = 0; i < actionSkillButtons.length; i += 1) {
+    const skill = skills[i];
+    if (!skill) {
+      continue;
+    }
+    const cost = skill.energy_cost > 0 ? ` ${skill.energy_cost}E` : "";
+    const cooldown = skill.cooldown_remaining > 0 ? ` (CD ${skill.cooldown_remaining})` : "";
+    actionSkillButtons[i].textContent = `${skill.name}${cost}${cooldown}`;
+    actionSkillButtons[i].disabled = !skill.ready;
+  }
+}
+
+function setChoiceLabels(choiceLabels) {
+  const buttons = [actionBasicBtn, ...actionSkillButtons];
+  for (let i = 0; i < buttons.length; i += 1) {
+    buttons[i].textContent = choiceLabels[i] || "-";
+    buttons[i].disabled = !choiceLabels[i];
+  }
+}
+
 function setTraitLabels(traitNames) {
   const labels = [traitNames[0], traitNames[1], traitNames[2], traitNames[3], traitNames[4]];
   actionBasicBtn.textContent = labels[0] || "Trait 1";
@@ -78,11 +113,65 @@ function setTraitLabels(traitNames) {
   }
 }
 
+const MAP_NODE_LABELS = {
+  Battle: "B",
+  Boss: "★",
+  Event: "?",
+  Shop: "$",
+  Rest: "R",
+};
+
+function renderMap() {
+  mapView.textContent = "";
+  if (currentHandle === null) {
+    return;
+  }
+
+  const floors = new Map();
+  for (const node of get_run_map(currentHandle)) {
+    if (!floors.has(node.floor)) {
+      floors.set(node.floor, []);
+    }
+    floors.get(node.floor).push(node);
+  }
+
+  let lastAct = null;
+  for (const floor of [...floors.keys()].sort((a, b) => a - b)) {
+    const column = document.createElement("div");
+    column.className = "map-floor";
+    const nodes = floors.get(floor).sort((a, b) => a.lane - b.lane);
+    if (lastAct !== null && nodes[0].act !== lastAct) {
+      column.classList.add("act-start");
+    }
+    lastAct = nodes[0].act;
+
+    for (const node of nodes) {
+      const button = document.createElement("button");
+      button.type = "button";
+      button.className = "map-node";
+      button.textContent = MAP_NODE_LABELS[node.node_type] || "·";
+      button.title = `${node.node_type} (floor ${node.floor})`;
+      if (node.visited) {
+        button.classList.add("visited");
+      }
+      if (node.current) {
+        button.classList.add("current");
+      }
+      button.disabled = !(uiMode === "path_choice" && node.selectable);
+      button.addEventListener("click", () => submitPathChoice(node.id));
+      column.appendChild(button);
+    }
+    mapView.appendChild(column);
+  }
+}
+
 function resetStatus() {
   statusNode.textContent = "-";
   statusBattle.textContent = "-";
   statusPlayerHp.textContent = "-";
   statusEnemyHp.textContent = "-";
+  statusEnemyIntent.textContent = "-";
+  targetSelect.textContent = "";
   statusResult.textContent = "진행 중";
   statusTraits.textContent = "-";
 }
@@ -96,6 +185,11 @@ function resetAll() {
   setInputPrompt("");
   selectableTraitIds = [];
   uiMode = "idle";
+  mapView.textContent = "";
+}
+
+function unitLabel(label, unitId) {
+  return unitId === undefined ? label : `${label}#${unitId}`;
 }
 
 function formatEventLine(event) {
@@ -103,21 +197,37 @@ function formatEventLine(event) {
     case "RunStart":
       return `[RunStart] seed=${event.seed}`;
     case "NodeStart":
-      return `[NodeStart] node=${event.node_index} type=${event.node_type}`;
+      return `[NodeStart] node=${event.node_index} floor=${event.floor} type=${event.node_type}`;
     case "BattleStart":
       return `[BattleStart] battle=${event.battle_index} enemy=${event.enemy_name}`;
     case "TurnReady":
-      return `[TurnReady] actor=${event.actor}`;
+      return `[TurnReady] actor=${unitLabel(event.actor, event.actor_id)}`;
+    case "TurnSkipped":
+      return `[TurnSkipped] actor=${unitLabel(event.actor, event.actor_id)} reason=${event.reason}`;
     case "ActionUsed":
-      return `[ActionUsed] actor=${event.actor} action=${event.action_name}`;
+      return `[ActionUsed] actor=${unitLabel(event.actor, event.actor_id)} action=${event.action_name}`;
     case "DamageDealt":
-      return `[DamageDealt] ${event.src} -> ${event.dst} dmg=${Number(event.amount).toFixed(2)} dst_hp=${Number(event.dst_hp_after).toFixed(2)}`;
+      return `[DamageDealt] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.miss ? "MISS" : `dmg=${Number(event.amount).toFixed(2)}${event.crit ? " CRIT" : ""}`} ${event.damage_type}${event.absorbed > 0 ? ` (absorbed=${Number(event.absorbed).toFixed(2)} hp=${Number(event.hp_damage).toFixed(2)})` : ""} dst_hp=${Number(event.dst_hp_after).toFixed(2)}${formatBreakdown(event.breakdown)}`;
+    case "Healed":
+      return `[Healed] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} +${Number(event.amount).toFixed(2)} overheal=${Number(event.overheal).toFixed(2)} via ${event.source} hp=${Number(event.dst_hp_after).toFixed(2)}`;
+    case "BarrierGranted":
+      return `[BarrierGranted] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} +${Number(event.amount).toFixed(2)} barrier=${Number(event.barrier_after).toFixed(2)}`;
     case "StatusApplied":
-      return `[StatusApplied] ${event.src} -> ${event.dst} ${event.status} stacks=${event.stacks} duration=${event.duration}`;
+      return `[StatusApplied] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.status} stacks=${event.stacks} (+${event.added}) duration=${event.duration}`;
     case "StatusTick":
-      return `[StatusTick] ${event.dst} ${event.status} amount=${Number(event.amount).toFixed(2)} hp=${Number(event.dst_hp_after).toFixed(2)}`;
+      return `[StatusTick] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.status} (${event.damage_type}) amount=${Number(event.amount).toFixed(2)}${event.absorbed > 0 ? ` absorbed=${Number(event.absorbed).toFixed(2)}` : ""} hp=${Number(event.dst_hp_after).toFixed(2)}`;
+    case "StatusDecayed":
+      return `[StatusDecayed] ${unitLabel(event.dst, event.dst_id)} ${event.status} stacks=${event.stacks}`;
+    case "StatusCleansed":
+      return `[StatusCleansed] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.status} stacks=${event.stacks}`;
+    case "StatusConsumed":
+      return `[StatusConsumed] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.status} stacks=${event.stacks} dmg=${Number(event.damage).toFixed(2)}`;
+    case "ImmunityGranted":
+      return `[ImmunityGranted] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.filter} duration=${event.duration}`;
+    case "StatusBlocked":
+      return `[StatusBlocked] ${unitLabel(event.src, event.src_id)} -> ${unitLabel(event.dst, event.dst_id)} ${event.status}`;
     case "StatusExpired":
-      return `[StatusExpired] ${event.dst} ${event.status}`;
+      return `[StatusExpired] ${unitLabel(event.dst, event.dst_id)} ${event.status}`;
     case "BattleEnd":
       return `[BattleEnd] result=${event.result} player_hp=${Number(event.player_hp_after).toFixed(2)}`;
     case "RunEnd":
@@ -126,6 +236,18 @@ function formatEventLine(event) {
       return `[TraitTriggered] ${event.trait_name} via ${event.trigger_type}`;
     case "TraitEffectApplied":
       return `[TraitEffectApplied] ${event.trait_name}: ${event.effect_summary}`;
+    case "TraitOffered":
+      return `[TraitOffered] choices=${event.choice_count}`;
+    case "TraitAcquired":
+      return `[TraitAcquired] ${event.trait_name} Lv${event.level} (${event.trait_count} owned)`;
+    case "NodeOffered":
+      return `[NodeOffered] ${event.title} choices=${event.choice_count}`;
+    case "NodeChoiceMade":
+      return `[NodeChoiceMade] ${event.choice}: ${event.effect_summary}`;
+    case "IntentChanged":
+      return `[IntentChanged] ${unitLabel(event.actor, event.actor_id)} -> ${unitLabel(event.target, event.target_id)} ${event.skill_name} est=${Number(event.estimated_damage).toFixed(2)}`;
+    case "GoldChanged":
+      return `[GoldChanged] ${event.delta >= 0 ? "+" : ""}${event.delta} gold=${event.gold_after}`;
     default:
       return `[UnknownEvent] ${JSON.stringify(event)}`;
   }
@@ -160,16 +282,65 @@ function appendEventLines(events) {
   logEl.scrollTop = logEl.scrollHeight;
 }
 
-function updateHudFromSnapshot(snapshot) {
-  const playerHpInt = Math.round(snapshot.player.hp);
-  const playerMaxHpInt = Math.round(snapshot.player.max_hp);
-  const enemyHpInt = Math.round(snapshot.enemy.hp);
-  const enemyMaxHpInt = Math.round(snapshot.enemy.max_hp);
+function formatBreakdown(steps) {
+  if (!steps || steps.length <= 2) {
+    return "";
+  }
+  const parts = steps.map((step) => `${step.stage}:${step.source}=${Number(step.amount).toFixed(2)}`);
+  return ` [${parts.join(" > ")}]`;
+}
+
+function statLabel(name, base, effective) {
+  const rounded = Math.round(effective * 10) / 10;
+  return Math.abs(effective - base) < 0.01 ? `${name} ${base}` : `${name} ${base}→${rounded}`;
+}
 
-  statusNode.textContent = String(snapshot.node_index);
+function unitStats(unit) {
+  const barrier = unit.barrier > 0 ? ` | Barrier ${Math.round(unit.barrier)}` : "";
+  return `${statLabel("ATK", unit.base_atk, unit.atk)} ${statLabel("SPD", unit.base_speed, unit.speed)}${barrier}`;
+}
+
+function unitDefenses(unit) {
+  const parts = unit.armor > 0 ? [`ARM ${Math.round(unit.armor)}`] : [];
+  if (unit.evasion > 0) {
+    parts.push(`EVA ${Math.round(unit.evasion * 100)}%`);
+  }
+  for (const res of unit.resistances) {
+    parts.push(`${res.damage_type} ${res.value > 0 ? "+" : ""}${Math.round(res.value * 100)}%`);
+  }
+  return parts.length ? ` | ${parts.join(" ")}` : "";
+}
+
+function updateHudFromSnapshot(snapshot) {
+  statusNode.textContent = snapshot.node_type
+    ? `${snapshot.node_index} (${snapshot.node_type}) | ${snapshot.gold}g`
+    : String(snapshot.node_index);
   statusBattle.textContent = String(snapshot.battle_index);
-  statusPlayerHp.textContent = `${playerHpInt}/${playerMaxHpInt} | ${snapshot.player.action_gauge.toFixed(1)}`;
-  statusEnemyHp.textContent = `${enemyHpInt}/${enemyMaxHpInt} | ${snapshot.enemy.action_gauge.toFixed(1)}`;
+  statusPlayerHp.textContent = snapshot.party.length
+    ? snapshot.party
+        .map(
+          (hero) =>
+            `#${hero.unit_id} ${hero.name} ${Math.round(hero.hp)}/${Math.round(hero.max_hp)} | ${hero.action_gauge.toFixed(1)} | E ${hero.energy}/${hero.max_energy} | ${unitStats(hero)}`,
+        )
+        .join(" / ")
+    : "-";
+  const enemies = snapshot.enemies;
+  statusEnemyHp.textContent = enemies.length
+    ? enemies
+        .map(
+          (enemy) =>
+            `#${enemy.unit_id} ${enemy.name} ${Math.round(enemy.hp)}/${Math.round(enemy.max_hp)} | ${enemy.action_gauge.toFixed(1)} | ${unitStats(enemy)}${unitDefenses(enemy)}`,
+        )
+        .join(" / ")
+    : "-";
+  const intents = enemies
+    .filter((enemy) => enemy.hp > 0 && enemy.intent)
+    .map(
+      (enemy) =>
+        `#${enemy.unit_id} ${enemy.intent.skill_name} -> ${enemy.intent.target} (~${Math.round(enemy.intent.estimated_damage)})`,
+    );
+  statusEnemyIntent.textContent = intents.length ? intents.join(" / ") : "-";
+  updateTargetOptions(enemies);
 
   if (snapshot.run_state === "ended") {
     statusResult.textContent = snapshot.run_result === "win" ? "승리" : "패배";
@@ -179,10 +350,53 @@ function updateHudFromSnapshot(snapshot) {
   }
 }
 
+function updateTargetOptions(enemies) {
+  const selected = targetSelect.value;
+  targetSelect.textContent = "";
+  const legal = currentHandle === null ? [] : [...get_legal_targets(currentHandle, "basic", 0)];
+  for (const enemy of enemies.filter((e) => legal.includes(e.unit_id))) {
+    const option = document.createElement("option");
+    option.value = String(enemy.unit_id);
+    option.textContent = `#${enemy.unit_id} ${enemy.name}`;
+    targetSelect.appendChild(option);
+  }
+  if ([...targetSelect.options].some((option) => option.value === selected)) {
+    targetSelect.value = selected;
+  }
+}
+
+function selectedTargetId() {
+  const value = Number.parseInt(targetSelect.value, 10);
+  return Number.isFinite(value) ? value : -1;
+}
+
 function processStepResult(result) {
   appendEventLines(result.events);
+  if (result.events.some((line) => line.includes('"kind":"NodeStart"'))) {
+    renderMap();
+  }
+
+  if (result.events.some((line) => line.includes('"kind":"TraitAcquired"'))) {
+    renderActiveTraits();
+  }
 
-  if (result.error) {
+  const choosing = uiMode === "trait_choice" || uiMode === "node_choice" || uiMode === "path_choice";
+  if (result.error && choosing) {
+    // Choice errors (e.g. not enough gold) keep the current choice open.
+    statusResult.textContent = `오류: ${result.error}`;
+    if (uiMode === "trait_choice") {
+      setChoiceLabels(get_trait_choices(currentHandle));
+    } else if (uiMode === "node_choice") {
+      setChoiceLabels(get_node_choices(currentHandle));
+    } else {
+      renderMap();
+    }
+    return;
+  }
+
+  // Rejected combat actions (target, cooldown, energy) keep the turn open.
+  const actionRejected = result.error && result.need_input && result.input_kind === "action";
+  if (result.error && !actionRejected) {
     statusResult.textContent = `오류: ${result.error}`;
     uiMode = "ended";
     setActionButtonsEnabled(false);
@@ -191,11 +405,44 @@ function processStepResult(result) {
     return;
   }
 
+  if (result.need_input && result.input_kind === "path_choice") {
+    statusResult.textContent = "경로 선택";
+    uiMode = "path_choice";
+    setActionButtonsEnabled(false);
+    setInputPrompt("Choose the next node on the map");
+    stopLoop();
+    renderMap();
+    return;
+  }
+
+  if (result.need_input && result.input_kind === "trait_choice") {
+    statusResult.textContent = "특성 보상";
+    uiMode = "trait_choice";
+    setChoiceLabels(get_trait_choices(currentHandle));
+    setInputPrompt("Choose a trait reward");
+    stopLoop();
+    return;
+  }
+
+  if (result.need_input && result.input_kind === "node_choice") {
+    statusResult.textContent = "선택 대기";
+    uiMode = "node_choice";
+    setChoiceLabels(get_node_choices(currentHandle));
+    setInputPrompt("Choose an option");
+    stopLoop();
+    return;
+  }
+
   if (result.need_input) {
-    statusResult.textContent = "입력 대기";
+    statusResult.textContent = actionRejected ? `오류: ${result.error}` : "입력 대기";
+    setCombatLabels(get_player_skills(currentHandle));
     uiMode = "need_input";
     setActionButtonsEnabled(true);
-    setInputPrompt("Choose action");
+    const hero = get_snapshot(currentHandle).party.find(
+      (unit) => unit.unit_id === result.input_unit_id,
+    );
+    setInputPrompt(hero ? `Choose action for ${unitLabel(hero.name, hero.unit_id)}` : "Choose action");
+    applySkillReadiness(hero);
     stopLoop();
     return;
   }
@@ -248,6 +495,9 @@ function startRun() {
   const safeSeed = Number.isNaN(seed) ? 1234 : seed;
 
   currentHandle = create_run(safeSeed, MAX_NODES);
+  if (partySelect.value) {
+    set_party(currentHandle, partySelect.value.split(","));
+  }
 
   const traitNames = get_selectable_trait_names();
   selectableTraitIds = get_selectable_trait_ids();
@@ -258,6 +508,7 @@ function startRun() {
   uiMode = "trait_select";
 
   updateHudFromSnapshot(get_snapshot(currentHandle));
+  renderMap();
 }
 
 function submitCombatAction(actionKind, actionArg) {
@@ -268,7 +519,41 @@ function submitCombatAction(actionKind, actionArg) {
   setActionButtonsEnabled(false);
   setInputPrompt("");
 
-  const result = step_with_action(currentHandle, 0.0, actionKind, actionArg);
+  const result = step_with_target(currentHandle, 0.0, actionKind, actionArg, selectedTargetId());
+  processStepResult(result);
+  updateHudFromSnapshot(get_snapshot(currentHandle));
+
+  if (uiMode === "running") {
+    startLoop();
+  }
+}
+
+function submitPathChoice(nodeId) {
+  if (currentHandle === null || uiMode !== "path_choice") {
+    return;
+  }
+
+  setInputPrompt("");
+
+  const result = step_with_action(currentHandle, 0.0, "path", nodeId);
+  processStepResult(result);
+  updateHudFromSnapshot(get_snapshot(currentHandle));
+  renderMap();
+
+  if (uiMode === "running") {
+    startLoop();
+  }
+}
+
+function submitTraitChoice(index) {
+  if (currentHandle === null || uiMode !== "trait_choice") {
+    return;
+  }
+
+  setActionButtonsEnabled(false);
+  setInputPrompt("");
+
+  const result = step_with_action(currentHandle, 0.0, "trait", index);
   processStepResult(result);
   updateHudFromSnapshot(get_snapshot(currentHandle));
 
@@ -277,6 +562,28 @@ function submitCombatAction(actionKind, actionArg) {
   }
 }
 
+function submitNodeChoice(index) {
+  if (currentHandle === null || uiMode !== "node_choice") {
+    return;
+  }
+
+  setActionButtonsEnabled(false);
+  setInputPrompt("");
+
+  const result = step_with_action(currentHandle, 0.0, "choice", index);
+  processStepResult(result);
+  updateHudFromSnapshot(get_snapshot(currentHandle));
+
+  if (uiMode === "running") {
+    startLoop();
+  }
+}
+
+function renderActiveTraits() {
+  const activeTraits = get_active_traits(currentHandle);
+  statusTraits.textContent = activeTraits.length > 0 ? activeTraits.join(", ") : "-";
+}
+
 function chooseTraitByButtonIndex(index) {
   if (currentHandle === null || uiMode !== "trait_select") {
     return;
@@ -287,14 +594,13 @@ function chooseTraitByButtonIndex(index) {
     return;
   }
 
-  const ok = set_active_trait(currentHandle, traitId);
+  const ok = add_active_trait(currentHandle, traitId);
   if (!ok) {
     statusResult.textContent = "특성 선택 실패";
     return;
   }
 
-  const activeTraits = get_active_traits(currentHandle);
-  statusTraits.textContent = activeTraits.length > 0 ? activeTraits.join(", ") : "-";
+  renderActiveTraits();
 
   const skills = get_player_skills(currentHandle);
   setCombatLabels(skills);
@@ -312,6 +618,16 @@ function onActionButton(index) {
     return;
   }
 
+  if (uiMode === "trait_choice") {
+    submitTraitChoice(index);
+    return;
+  }
+
+  if (uiMode === "node_choice") {
+    submitNodeChoice(index);
+    return;
+  }
+
   if (uiMode === "need_input") {
     if (index === 0) {
       submitCombatAction("basic", -1);
@@ -340,8 +656,21 @@ resetBtn.addEventListener("click", () => {
   resetAll();
 });
 
+function populatePartyOptions() {
+  const heroIds = get_selectable_hero_ids();
+  const heroNames = get_selectable_hero_names();
+  partySelect.innerHTML = "";
+  for (let size = 1; size <= heroIds.length; size += 1) {
+    const option = document.createElement("option");
+    option.value = heroIds.slice(0, size).join(",");
+    option.textContent = heroNames.slice(0, size).join(" + ");
+    partySelect.appendChild(option);
+  }
+}
+
 async function boot() {
   await init();
+  populatePartyOptions();
   bootStatus.textContent = "WASM ready";
   console.log("sim started");
 